@@ -1,158 +1,1473 @@
-use std::collections::{HashMap, BTreeMap};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::rc::Rc;
 
-use battlebit_api::{ServerData, BBApi, Gamemode};
+use battlebit_api::ServerData;
 
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 use yew::prelude::*;
-use gloo::{timers::callback::Timeout, console::log};
+use js_sys::Date;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{AbortController, AbortSignal, HtmlAnchorElement, HtmlInputElement, ShareData, UrlSearchParams};
+use gloo::events::EventListener;
+use gloo::net::http::Request;
+use gloo::timers::callback::{Interval, Timeout};
 
 
-use ybc::TileCtx::{Ancestor, Child, Parent};
+use ybc::TileCtx::{Ancestor, Parent};
+
+use crate::components::{region_color, trend_arrow, BarChart, CountItem, CountList, DonutChart, ErrorBanner, FilterBar, PlayerSummary, RelativeTime, ServerDetails, ServerTable, StatTile, Theme, ThemeToggle};
+use crate::filter::{anti_cheat_to_string, FilterState};
+use crate::format::{format_count, format_delta};
+use crate::stats::{build_version, gamemode_to_string, map_to_string, region_to_string, sum_players, Stats};
+use crate::storage;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum FetchError {
+    Offline,
+    Network,
+    Timeout,
+    Status(u16),
+    /// HTTP 429, with the `Retry-After` delay in seconds if the API sent one.
+    RateLimited(Option<u32>),
+    InvalidResponse,
+    Other(String),
+}
+
+impl FetchError {
+    fn message(&self) -> String {
+        match self {
+            FetchError::Offline => String::from("You appear to be offline"),
+            FetchError::Network => String::from("Could not reach the BattleBit API"),
+            FetchError::Timeout => String::from("The BattleBit API took too long to respond"),
+            FetchError::Status(status) => format!("The BattleBit API responded with status {status}"),
+            FetchError::RateLimited(_) => String::from("The BattleBit API is rate limiting requests, slowing down"),
+            FetchError::InvalidResponse => String::from("The BattleBit API returned an unexpected response"),
+            FetchError::Other(err) => format!("Failed to fetch server list ({err})"),
+        }
+    }
+}
+
+impl From<gloo::net::Error> for FetchError {
+    fn from(err: gloo::net::Error) -> Self {
+        match err {
+            gloo::net::Error::JsError(err) => {
+                if !gloo::utils::window().navigator().on_line() {
+                    FetchError::Offline
+                } else if err.name == "TypeError" {
+                    FetchError::Network
+                } else {
+                    FetchError::Other(err.message)
+                }
+            },
+            gloo::net::Error::SerdeError(_) => FetchError::InvalidResponse,
+            gloo::net::Error::GlooError(err) => FetchError::Other(err),
+        }
+    }
+}
+
+/// `Retry-After` is either a number of seconds or an HTTP date.
+fn parse_retry_after(value: &str) -> Option<u32> {
+    if let Ok(secs) = value.trim().parse() {
+        return Some(secs);
+    }
+
+    let retry_at = Date::parse(value);
+
+    (!retry_at.is_nan()).then(|| ((retry_at - Date::now()) / 1000.0).ceil().max(0.0) as u32)
+}
+
+const DEFAULT_API_URL: &str = "https://publicapi.battlebit.cloud/";
+
+/// Same request as `BBApi::server_list`, but abortable through `signal`.
+async fn fetch_server_list(api_url: &str, signal: AbortSignal) -> Result<Vec<ServerData>, FetchError> {
+    let url = format!("{}/Servers/GetServerList", api_url.trim_end_matches('/'));
+    let response = Request::get(&url)
+        .abort_signal(Some(&signal))
+        .send()
+        .await?;
+
+    if response.status() == 429 {
+        let retry_after = response.headers().get("Retry-After").and_then(|value| parse_retry_after(&value));
+        return Err(FetchError::RateLimited(retry_after));
+    }
+
+    if !response.ok() {
+        return Err(FetchError::Status(response.status()));
+    }
+
+    let data = response.binary().await?;
+    let data = data.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(&data);
+
+    serde_json::from_slice(data).map_err(|_| FetchError::InvalidResponse)
+}
+
+/// Headline numbers of a previous update, used to show how they changed since.
+#[derive(Clone, PartialEq)]
+pub struct Snapshot {
+    players: usize,
+    queued: usize,
+    servers: usize,
+    region_players: HashMap<String, usize>,
+}
+
+/// Headline numbers and filters encoded into a share link's URL fragment.
+#[derive(Clone, PartialEq)]
+pub struct SharedView {
+    at: f64,
+    players: usize,
+    queued: usize,
+    servers: usize,
+}
+
+impl SharedView {
+    fn to_fragment(&self, filters: &FilterState) -> String {
+        let stats = format!("at={}&players={}&queued={}&servers={}", (self.at / 1000.0) as u64, self.players, self.queued, self.servers);
+        let query = filters.to_query();
+
+        if query.is_empty() { stats } else { format!("{stats}&{query}") }
+    }
+
+    /// Returns `None` for fragments that weren't produced by `to_fragment`.
+    fn from_fragment(fragment: &str) -> Option<(SharedView, FilterState)> {
+        let params = UrlSearchParams::new_with_str(fragment).ok()?;
+        let number = |key: &str| params.get(key).and_then(|value| value.parse::<u64>().ok());
+
+        let shared_view = SharedView {
+            at: number("at")? as f64 * 1000.0,
+            players: number("players")? as usize,
+            queued: number("queued")? as usize,
+            servers: number("servers")? as usize,
+        };
+
+        Some((shared_view, FilterState::from_query(fragment)))
+    }
+
+    fn from_url() -> Option<(SharedView, FilterState)> {
+        let hash = gloo::utils::window().location().hash().ok()?;
+        SharedView::from_fragment(hash.strip_prefix('#').unwrap_or(&hash))
+    }
+}
+
+/// Servers and players behind one region, map or gamemode in a `StatsSnapshot`.
+#[derive(Serialize, Deserialize)]
+pub struct Breakdown {
+    name: String,
+    servers: usize,
+    players: usize,
+}
+
+impl From<(String, usize, usize)> for Breakdown {
+    fn from((name, servers, players): (String, usize, usize)) -> Self {
+        Breakdown { name, servers, players }
+    }
+}
+
+/// Aggregates of the filtered servers, exported as JSON for external tooling.
+#[derive(Serialize, Deserialize)]
+pub struct StatsSnapshot {
+    generated_at: String,
+    /// When the underlying server list was fetched.
+    #[serde(default)]
+    updated_at: Option<String>,
+    players: usize,
+    queued: usize,
+    servers: usize,
+    regions: Vec<Breakdown>,
+    maps: Vec<Breakdown>,
+    gamemodes: Vec<Breakdown>,
+}
+
+/// Bump whenever a field of `JsonFeed` is added, removed or changes meaning.
+const JSON_FEED_VERSION: u32 = 1;
+
+/// Stable JSON contract rendered by `?format=json`:
+///
+/// `{ version, generated_at, updated_at, totals: { players, queued, servers },
+///    regions, maps, gamemodes }`, where each breakdown is a list of
+/// `{ name, servers, players }` sorted by players.
+///
+/// Until the first fetch lands, `updated_at` and `totals` are `null` and the
+/// breakdowns are empty.
+#[derive(Serialize)]
+struct JsonFeed<'a> {
+    version: u32,
+    generated_at: &'a str,
+    updated_at: Option<&'a str>,
+    totals: Option<JsonFeedTotals>,
+    regions: &'a [Breakdown],
+    maps: &'a [Breakdown],
+    gamemodes: &'a [Breakdown],
+}
+
+#[derive(Serialize)]
+struct JsonFeedTotals {
+    players: usize,
+    queued: usize,
+    servers: usize,
+}
+
+impl JsonFeed<'_> {
+    fn pending(generated_at: &str) -> JsonFeed<'_> {
+        JsonFeed {
+            version: JSON_FEED_VERSION,
+            generated_at,
+            updated_at: None,
+            totals: None,
+            regions: &[],
+            maps: &[],
+            gamemodes: &[],
+        }
+    }
+
+    fn render(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+}
+
+fn prometheus_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+impl StatsSnapshot {
+    fn to_json_feed(&self) -> String {
+        let feed = JsonFeed {
+            version: JSON_FEED_VERSION,
+            generated_at: &self.generated_at,
+            updated_at: self.updated_at.as_deref(),
+            totals: Some(JsonFeedTotals {
+                players: self.players,
+                queued: self.queued,
+                servers: self.servers,
+            }),
+            regions: &self.regions,
+            maps: &self.maps,
+            gamemodes: &self.gamemodes,
+        };
+
+        feed.render()
+    }
+
+    /// Renders the snapshot in the Prometheus text exposition format.
+    fn to_prometheus(&self) -> String {
+        let mut output = String::new();
+
+        let mut gauge = |name: &str, help: &str, samples: Vec<(String, usize)>| {
+            output.push_str(&format!("# HELP {name} {help}\n# TYPE {name} gauge\n"));
+
+            for (labels, value) in samples {
+                output.push_str(&format!("{name}{labels} {value}\n"));
+            }
+        };
+
+        let breakdown = |label: &str, items: &[Breakdown], value: fn(&Breakdown) -> usize| {
+            items.iter()
+                .map(|item| (format!("{{{label}=\"{}\"}}", prometheus_label(&item.name)), value(item)))
+                .collect::<Vec<(String, usize)>>()
+        };
+
+        gauge("battlebit_players_total", "Players on the listed servers.", vec![(String::new(), self.players)]);
+        gauge("battlebit_queued_players_total", "Players waiting in server queues.", vec![(String::new(), self.queued)]);
+        gauge("battlebit_servers_total", "Listed servers.", vec![(String::new(), self.servers)]);
+        gauge("battlebit_region_players", "Players per region.", breakdown("region", &self.regions, |item| item.players));
+        gauge("battlebit_region_servers", "Servers per region.", breakdown("region", &self.regions, |item| item.servers));
+        gauge("battlebit_gamemode_players", "Players per gamemode.", breakdown("gamemode", &self.gamemodes, |item| item.players));
+        gauge("battlebit_gamemode_servers", "Servers per gamemode.", breakdown("gamemode", &self.gamemodes, |item| item.servers));
+
+        output
+    }
+}
+
+/// Plain-text renderings selected with `?format=`, replacing the dashboard.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum RawFormat {
+    Prometheus,
+    Json,
+}
+
+impl RawFormat {
+    fn from_url() -> Option<RawFormat> {
+        let query = gloo::utils::window().location().search().unwrap_or_default();
+
+        match UrlSearchParams::new_with_str(&query).ok()?.get("format")?.as_str() {
+            "prometheus" => Some(RawFormat::Prometheus),
+            "json" => Some(RawFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+/// Provided to the whole dashboard so components can `use_context` instead of threading props.
+#[derive(Clone, PartialEq, Default)]
+pub struct ServerContext {
+    /// Servers matching the active filters.
+    pub servers: Rc<Vec<ServerData>>,
+    pub filters: FilterState,
+}
+
+#[derive(Properties, PartialEq, Default)]
+pub struct AppProps {
+    /// Base URL of the BattleBit API, e.g. a staging mirror or self-hosted proxy.
+    #[prop_or_default]
+    pub api_url: Option<AttrValue>,
+    /// Renders only a compact widget, for embedding in an iframe. Also enabled by `?embed=1`.
+    #[prop_or_default]
+    pub embed: bool,
+}
+
+fn embed_from_url() -> bool {
+    let query = gloo::utils::window().location().search().unwrap_or_default();
+
+    UrlSearchParams::new_with_str(&query)
+        .is_ok_and(|params| params.get("embed").as_deref() == Some("1"))
+}
 
 pub enum Msg {
     UpdateData,
+    Poll,
     Updated(Vec<ServerData>),
-    UpdateFailed,
+    UpdateFailed(FetchError),
+    ToggleVoxelGrouping,
+    ThresholdChanged(u32),
+    ToggleMapVariants,
+    FilterChanged(FilterState),
+    DismissError,
+    Tick,
+    WentOnline,
+    WentOffline,
+    VisibilityChanged(bool),
+    IntervalChanged(u32),
+    ThemeChanged(Theme),
+    DownloadCsv,
+    DownloadJson,
+    CopySummary,
+    ShareView,
+    ShareLink,
+    DismissSharedView,
+    Copied(bool),
+    SnapshotFileSelected(gloo::file::File),
+    SnapshotImported(Result<StatsSnapshot, String>),
+    ClearImportedSnapshot,
+    SelectServer(ServerData),
+    ToggleFavorite(String),
+    CloseModal,
+    ClearCopyStatus,
+    ClearServerChanges,
+}
+
+/// Matches the `<title>` in index.html.
+const BASE_TITLE: &str = "Battlebit Server Statistics";
+
+const DEFAULT_REFRESH_SECS: u32 = 60;
+
+/// Lowest refresh interval accepted, so the API doesn't get hammered.
+const MIN_REFRESH_SECS: u32 = 10;
+
+const REFRESH_OPTIONS: [(u32, &str); 4] = [(15, "15s"), (30, "30s"), (60, "60s"), (300, "5min")];
+
+/// How long a fetch may take before it's aborted as timed out.
+const FETCH_TIMEOUT_MS: u32 = 20_000;
+
+/// Delay before the first retry after a failed fetch, doubled on every further failure.
+const RETRY_DELAY_MS: u32 = 10_000;
+
+/// Upper bound for the exponential backoff.
+const MAX_RETRY_DELAY_MS: u32 = 300_000;
+
+/// Longest delay setTimeout accepts, so a huge Retry-After can't overflow it.
+const MAX_RETRY_AFTER_MS: u32 = i32::MAX as u32;
+
+/// Amount of player count samples kept, two hours worth at the default refresh interval.
+const HISTORY_CAPACITY: usize = 120;
+
+const SPARKLINE_POINTS: usize = 60;
+
+/// Slack on top of the refresh interval before data is flagged as possibly outdated.
+const STALE_GRACE_SECS: u64 = 30;
+
+/// How long servers that appeared or went away with an update stay highlighted.
+const NEW_SERVER_HIGHLIGHT_MS: u32 = 5_000;
+
+/// How many entries the map and gamemode lists show before collapsing the rest.
+const DEFAULT_TOP_N: usize = 8;
+
+const HISTOGRAM_BUCKET_SIZE: u32 = 32;
+
+const MAP_CHART_ITEMS: usize = 10;
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn servers_to_csv(servers: &[&ServerData]) -> String {
+    servers.iter().fold(String::from("name,map,gamemode,region,players,queued\n"), |mut csv, server| {
+        let row = [
+            csv_field(server.name()),
+            csv_field(server.map()),
+            csv_field(&gamemode_to_string(server.gamemode(), false)),
+            csv_field(&region_to_string(server.region())),
+            server.player_count().to_string(),
+            server.queued_player_count().to_string(),
+        ];
+
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+        csv
+    })
+}
+
+fn copy_to_clipboard(ctx: &yew::prelude::Context<App>, text: String) {
+    let clipboard = gloo::utils::window().navigator().clipboard();
+
+    // Only available in secure contexts.
+    if clipboard.is_undefined() {
+        ctx.link().send_message(Msg::Copied(false));
+        return;
+    }
+
+    ctx.link().send_future(async move {
+        Msg::Copied(JsFuture::from(clipboard.write_text(&text)).await.is_ok())
+    });
+}
+
+/// Hands `contents` to the browser as a file download.
+fn download(filename: &str, mime_type: &str, contents: &str) {
+    let url = gloo::file::ObjectUrl::from(gloo::file::Blob::new_with_options(contents, Some(mime_type)));
+
+    if let Ok(element) = gloo::utils::document().create_element("a") {
+        let anchor = element.unchecked_into::<HtmlAnchorElement>();
+        anchor.set_href(&url);
+        anchor.set_download(filename);
+        anchor.click();
+    }
+
+    // Revoking the object URL right away can cancel the download in some browsers.
+    Timeout::new(1_000, move || drop(url)).forget();
 }
 
 pub struct App {
     server_data: Vec<ServerData>,
     timer_handle: Option<Timeout>,
+    poll_handle: Option<Interval>,
+    abort_controller: Option<AbortController>,
+    refresh_secs: u32,
+    theme: Theme,
+    embed: bool,
+    /// Whether the browser supports the Web Share API.
+    can_share: bool,
+    raw_format: Option<RawFormat>,
+    copy_status: Option<bool>,
+    shared_view: Option<SharedView>,
+    imported_snapshot: Option<Result<StatsSnapshot, String>>,
+    selected_server: Option<ServerData>,
+    favorites: HashSet<String>,
+    _import_reader: Option<gloo::file::callbacks::FileReader>,
+    copy_status_handle: Option<Timeout>,
+    /// Names of every server as of the last update, ignoring the filters. Kept apart from
+    /// `previous_snapshot`, which filter changes reset.
+    server_names: Option<HashSet<String>>,
+    /// Servers that appeared and went away with the last update, highlighted for a moment.
+    new_servers: HashSet<String>,
+    removed_servers: Vec<String>,
+    server_changes_handle: Option<Timeout>,
+    group_voxel: bool,
+    player_threshold: u32,
+    merge_map_variants: bool,
+    filters: FilterState,
+    is_loading: bool,
+    /// Whether `server_data` was seeded from localStorage and not refreshed yet.
+    is_cached: bool,
+    is_fetching: bool,
+    consecutive_failures: u32,
+    error: Option<String>,
+    is_rate_limited: bool,
+    last_updated: Option<f64>,
+    now: f64,
+    _clock_handle: Interval,
+    is_offline: bool,
+    _online_listener: EventListener,
+    _offline_listener: EventListener,
+    is_hidden: bool,
+    _visibility_listener: EventListener,
+    history: VecDeque<(f64, usize)>,
+    previous_snapshot: Option<Snapshot>,
+    server_context: ServerContext,
 }
 
 impl App {
-    fn region_count(&self) -> HashMap<String, usize> {
-        let counts = self.server_data.iter().fold(HashMap::new(), |mut counts, server| {
-            let region = server.region().to_string();
+    fn seconds_since_update(&self) -> Option<u64> {
+        self.last_updated.map(|timestamp| ((self.now - timestamp).max(0.0) / 1000.0) as u64)
+    }
 
-            match counts.get_mut(&region) {
-                Some(val) => *val += 1,
-                None => { counts.insert(region, 1usize); },
-            }
+    fn record_history(&mut self) {
+        let (active, _) = self.global_player_count();
 
-            counts
-        });
+        self.history.push_back((self.now, active));
+
+        while self.history.len() > HISTORY_CAPACITY {
+            self.history.pop_front();
+        }
 
-        counts
+        storage::save_history(&self.history);
     }
 
-    fn map_count(&self) -> HashMap<String, usize> {
-        self.server_data.iter().fold(HashMap::new(), |mut counts, server| {
-            match counts.get_mut(server.map()) {
-                Some(val) => *val += 1,
-                None => { counts.insert(server.map().clone(), 1usize); },
-            }
-            counts
-        })
-    }
-
-    fn player_count(&self) -> (usize, usize) {
-        self.server_data.iter().fold((0, 0), |mut counts, server| {
-            counts.0 += *server.player_count() as usize;
-            counts.1 += *server.queued_player_count() as usize;
-
-            counts
-        })
-    }
-
-    fn gamemode_count(&self) -> HashMap<String, usize> {
-        fn gamemode_to_string(gamemode: &Gamemode) -> String {
-            match gamemode {
-                Gamemode::InfanteryConquest => String::from("Infantery Conquest"),
-                Gamemode::TeamDeathmatch => String::from("Team Deathmatch"),
-                Gamemode::CaptureTheFlag => String::from("Capture The Flag"),
-                Gamemode::VoxelFortify => String::from("Voxel Fortify"),
-                Gamemode::VoxelTrench => String::from("Voxel Trench"),
-                Gamemode::FreeForAll => String::from("Free For All"),
-                Gamemode::Gamemode19 => String::from("Gamemode 19"),
-                _ => gamemode.to_string()
-            }
+    fn history(&self) -> &VecDeque<(f64, usize)> {
+        &self.history
+    }
+
+    fn recent_history(&self, count: usize) -> Vec<usize> {
+        self.history().iter()
+            .skip(self.history().len().saturating_sub(count))
+            .map(|(_, players)| *players)
+            .collect()
+    }
+
+    fn peak_players(&self) -> Option<usize> {
+        self.history().iter().map(|(_, players)| *players).max()
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        let stats = self.stats();
+        let (players, queued) = stats.player_count();
+
+        Snapshot {
+            players,
+            queued,
+            servers: stats.server_count(),
+            region_players: stats.per_region_player_count()
+                .into_iter()
+                .map(|(region, players)| (region, players.0))
+                .collect(),
         }
+    }
+
+    fn stats_snapshot(&self) -> StatsSnapshot {
+        let stats = self.stats();
+        let (players, queued) = stats.player_count();
+        let region_players = stats.per_region_player_count();
+
+        StatsSnapshot {
+            generated_at: String::from(Date::new_0().to_iso_string()),
+            updated_at: self.last_updated.map(|timestamp| String::from(Date::new(&JsValue::from_f64(timestamp)).to_iso_string())),
+            players,
+            queued,
+            servers: stats.server_count(),
+            regions: stats.region_count()
+                .into_iter()
+                .map(|(region, servers)| {
+                    let players = region_players.get(&region).map_or(0, |players| players.0);
+                    (region, servers, players)
+                })
+                .sorted_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.cmp(&b.0)))
+                .map(Breakdown::from)
+                .collect(),
+            maps: stats.maps_by_players().into_iter().map(Breakdown::from).collect(),
+            gamemodes: stats.gamemodes_by_players().into_iter().map(Breakdown::from).collect(),
+        }
+    }
+
+    /// One-line summary meant for pasting into chats.
+    fn summary_text(&self) -> String {
+        let stats = self.stats();
+        let (players, queued) = stats.player_count();
+        let scope = if self.filters.active_count() > 0 { "Filtered" } else { "Global" };
 
-        self.server_data.iter().fold(HashMap::new(), |mut counts, server| {
-            let gamemode = gamemode_to_string(server.gamemode());
+        let mut summary = format!(
+            "{scope}: {} players ({} queued) across {} servers",
+            format_count(players),
+            format_count(queued),
+            format_count(stats.server_count()),
+        );
 
-            match counts.get_mut(&gamemode) {
-                Some(val) => *val += 1,
-                None => { counts.insert(gamemode, 1usize); },
+        if let Some((map, _)) = stats.busiest_map() {
+            summary.push_str(&format!(" | Top map: {map}"));
+        }
+
+        if let Some((region, _)) = stats.busiest_region() {
+            summary.push_str(&format!(" | Top region: {region}"));
+        }
+
+        summary
+    }
+
+    fn trends(&self, stats: &Stats) -> Option<(i64, i64, i64)> {
+        let previous = self.previous_snapshot.as_ref()?;
+        let (players, queued) = stats.player_count();
+
+        Some((
+            players as i64 - previous.players as i64,
+            queued as i64 - previous.queued as i64,
+            stats.server_count() as i64 - previous.servers as i64,
+        ))
+    }
+
+    fn region_trends(&self, stats: &Stats) -> Option<HashMap<String, i64>> {
+        let previous = self.previous_snapshot.as_ref()?;
+
+        Some(stats.per_region_player_count()
+            .into_iter()
+            .map(|(region, players)| {
+                let before = previous.region_players.get(&region).copied().unwrap_or(0);
+                let delta = players.0 as i64 - before as i64;
+                (region, delta)
+            })
+            .collect())
+    }
+
+    fn polling_paused(&self) -> bool {
+        self.is_offline || self.is_hidden
+    }
+
+    fn is_stale(&self) -> bool {
+        let stale_after = self.refresh_secs as u64 + STALE_GRACE_SECS;
+        self.seconds_since_update().is_some_and(|seconds| seconds > stale_after)
+    }
+
+    fn refresh_ms(&self) -> u32 {
+        self.refresh_secs.saturating_mul(1000)
+    }
+
+    fn retry_delay_ms(&self) -> u32 {
+        let exponent = self.consecutive_failures.saturating_sub(1).min(16);
+
+        RETRY_DELAY_MS.saturating_mul(1 << exponent).min(MAX_RETRY_DELAY_MS)
+    }
+
+    fn filtered_servers(&self) -> Vec<&ServerData> {
+        self.server_data.iter()
+            .filter(|server| self.filters.matches(server, self.merge_map_variants))
+            .collect()
+    }
+
+    fn stats(&self) -> Stats<'_> {
+        Stats::new(self.filtered_servers(), self.merge_map_variants, self.group_voxel)
+    }
+
+    fn available_regions(&self) -> Vec<String> {
+        self.server_data.iter()
+            .map(|server| region_to_string(server.region()))
+            .unique()
+            .sorted()
+            .collect()
+    }
+
+    fn available_maps(&self) -> Vec<String> {
+        self.server_data.iter()
+            .map(|server| map_to_string(server.map(), self.merge_map_variants))
+            .unique()
+            .sorted()
+            .collect()
+    }
+
+    fn available_anti_cheats(&self) -> Vec<String> {
+        self.server_data.iter()
+            .map(|server| *server.anti_cheat())
+            .sorted()
+            .dedup()
+            .map(|anti_cheat| anti_cheat_to_string(&anti_cheat))
+            .collect()
+    }
+
+    fn available_builds(&self) -> Vec<String> {
+        self.server_data.iter()
+            .map(|server| server.build().clone())
+            .unique()
+            .sorted_by(|a, b| build_version(b).cmp(&build_version(a)).then_with(|| b.cmp(a)))
+            .collect()
+    }
+
+    fn available_gamemodes(&self) -> Vec<String> {
+        self.server_data.iter()
+            .map(|server| gamemode_to_string(server.gamemode(), false))
+            .unique()
+            .sorted()
+            .collect()
+    }
+
+    /// Clears filter values no listed server has, e.g. from a hand-edited URL. They would
+    /// otherwise filter out every server while the dropdowns read "All".
+    fn drop_unknown_filters(&mut self) {
+        let is_unknown = |value: &Option<String>, available: Vec<String>| value.as_ref().is_some_and(|value| !available.contains(value));
+
+        if is_unknown(&self.filters.region, self.available_regions()) {
+            self.filters.region = None;
+        }
+        if is_unknown(&self.filters.gamemode, self.available_gamemodes()) {
+            self.filters.gamemode = None;
+        }
+        if is_unknown(&self.filters.map, self.available_maps()) {
+            self.filters.map = None;
+        }
+        if is_unknown(&self.filters.build, self.available_builds()) {
+            self.filters.build = None;
+        }
+    }
+
+    fn global_player_count(&self) -> (usize, usize) {
+        sum_players(&self.server_data)
+    }
+
+    fn offline_favorites(&self) -> Vec<String> {
+        self.favorites.iter()
+            .filter(|name| !self.server_data.iter().any(|server| server.name() == *name))
+            .cloned()
+            .sorted()
+            .collect()
+    }
+
+    /// Puts the live player count in the tab title, as long as the data can be trusted.
+    /// Polling pauses in background tabs, so age alone doesn't drop the count there.
+    fn update_title(&self) {
+        let is_stale = self.is_stale() && !self.is_hidden;
+
+        let title = if self.is_loading || self.error.is_some() || is_stale {
+            String::from(BASE_TITLE)
+        } else {
+            format!("({}) {BASE_TITLE}", format_count(self.global_player_count().0))
+        };
+
+        gloo::utils::document().set_title(&title);
+    }
+}
+
+impl App {
+    fn fetch(&mut self, ctx: &yew::prelude::Context<Self>) {
+        self.is_fetching = true;
+
+        let controller = AbortController::new().unwrap();
+        let signal = controller.signal();
+
+        // Without this a hung request would leave `is_fetching` set and stall polling for good.
+        let timeout = {
+            let controller = controller.clone();
+            Timeout::new(FETCH_TIMEOUT_MS, move || controller.abort())
+        };
+
+        self.abort_controller = Some(controller);
+
+        let api_url = ctx.props().api_url.clone()
+            .unwrap_or(AttrValue::Static(DEFAULT_API_URL));
+
+        ctx.link().send_future(async move {
+            let result = fetch_server_list(&api_url, signal.clone()).await;
+            drop(timeout);
+
+            match result {
+                Ok(data) => Msg::Updated(data),
+                Err(_) if signal.aborted() => Msg::UpdateFailed(FetchError::Timeout),
+                Err(err) => Msg::UpdateFailed(err),
             }
+        });
+    }
+
+    fn start_polling(&mut self, ctx: &yew::prelude::Context<Self>) {
+        if let Some(handle) = self.poll_handle.take() {
+            handle.cancel();
+        }
+
+        let handle = {
+            let link = ctx.link().clone();
+            Interval::new(self.refresh_ms(), move || link.send_message(Msg::Poll))
+        };
+
+        self.poll_handle = Some(handle);
+    }
+
+    /// Schedules a one-off update in `delay_ms`, replacing any pending one.
+    fn schedule_next(&mut self, ctx: &yew::prelude::Context<Self>, delay_ms: u32) {
+        if let Some(handle) = self.timer_handle.take() {
+            handle.cancel();
+        }
+
+        let handle = {
+            let link = ctx.link().clone();
+            Timeout::new(delay_ms, move || link.send_message(Msg::UpdateData))
+        };
+
+        self.timer_handle = Some(handle);
+    }
+
+    fn refresh_server_context(&mut self) {
+        self.server_context = ServerContext {
+            servers: Rc::new(self.filtered_servers().into_iter().cloned().collect()),
+            filters: self.filters.clone(),
+        };
+    }
 
-            counts
-        })
+    fn track_server_changes(&mut self, ctx: &yew::prelude::Context<Self>) {
+        let current = self.server_data.iter().map(|server| server.name().clone()).collect::<HashSet<String>>();
+        let Some(previous) = self.server_names.replace(current.clone()) else {
+            return;
+        };
+
+        self.new_servers = current.difference(&previous).cloned().collect();
+        self.removed_servers = previous.difference(&current).cloned().sorted().collect();
+
+        self.server_changes_handle = (!self.new_servers.is_empty() || !self.removed_servers.is_empty()).then(|| {
+            let link = ctx.link().clone();
+            Timeout::new(NEW_SERVER_HIGHLIGHT_MS, move || link.send_message(Msg::ClearServerChanges))
+        });
+    }
+
+    /// Cancels both the regular poll and any pending retry.
+    fn stop_polling(&mut self) {
+        if let Some(handle) = self.timer_handle.take() {
+            handle.cancel();
+        }
+
+        if let Some(handle) = self.poll_handle.take() {
+            handle.cancel();
+        }
+    }
+
+    fn view_embed(&self) -> Html {
+        let stats = self.stats();
+        let (players, queued) = stats.player_count();
+
+        html!{
+            <ybc::Box classes={classes!("has-text-centered", self.is_stale().then_some("is-stale"))}>
+                if self.is_loading {
+                    <progress class="progress is-small is-primary" max="100"></progress>
+                } else {
+                    <ybc::Title size={ybc::HeaderSize::Is2} classes={classes!("mb-1")}>{format_count(players)}</ybc::Title>
+                    <ybc::Subtitle size={ybc::HeaderSize::Is6}>
+                        {format!("players online, {} queued across {} servers", format_count(queued), format_count(stats.server_count()))}
+                    </ybc::Subtitle>
+                }
+                if let Some(timestamp) = self.last_updated {
+                    <p class="is-size-7">{"Updated "}<RelativeTime {timestamp} /></p>
+                }
+            </ybc::Box>
+        }
     }
 }
 
 impl Component for App {
     type Message = Msg;
-    type Properties = ();
+    type Properties = AppProps;
 
     fn create(ctx: &yew::prelude::Context<Self>) -> Self {
         ctx.link().send_message(Msg::UpdateData);
 
-        Self {
-            server_data: Vec::new(),
+        let clock_handle = {
+            let link = ctx.link().clone();
+            Interval::new(1_000, move || link.send_message(Msg::Tick))
+        };
+
+        let (last_updated, server_data) = match storage::load_servers(Date::now()) {
+            Some((saved_at, servers)) => (Some(saved_at), servers),
+            None => (None, Vec::new()),
+        };
+
+        let (shared_view, filters) = match SharedView::from_url() {
+            Some((shared_view, filters)) => (Some(shared_view), filters),
+            None => (None, FilterState::from_url()),
+        };
+
+        let mut history = storage::load_history(Date::now());
+        history.drain(..history.len().saturating_sub(HISTORY_CAPACITY));
+
+        let window = gloo::utils::window();
+        let online_listener = {
+            let link = ctx.link().clone();
+            EventListener::new(&window, "online", move |_| link.send_message(Msg::WentOnline))
+        };
+        let offline_listener = {
+            let link = ctx.link().clone();
+            EventListener::new(&window, "offline", move |_| link.send_message(Msg::WentOffline))
+        };
+
+        let document = gloo::utils::document();
+        let visibility_listener = {
+            let link = ctx.link().clone();
+            EventListener::new(&document, "visibilitychange", move |_| {
+                link.send_message(Msg::VisibilityChanged(!gloo::utils::document().hidden()))
+            })
+        };
+
+        let mut app = Self {
+            server_data,
             timer_handle: None,
+            poll_handle: None,
+            abort_controller: None,
+            // Only the offered intervals are accepted, anything else was edited in by hand.
+            refresh_secs: storage::load_refresh_secs()
+                .filter(|secs| REFRESH_OPTIONS.iter().any(|(option, _)| option == secs))
+                .unwrap_or(DEFAULT_REFRESH_SECS),
+            theme: storage::load_theme()
+                .and_then(|theme| Theme::from_key(&theme))
+                .unwrap_or_default(),
+            embed: ctx.props().embed || embed_from_url(),
+            can_share: js_sys::Reflect::has(&window.navigator(), &JsValue::from_str("share")).unwrap_or(false),
+            raw_format: RawFormat::from_url(),
+            copy_status: None,
+            shared_view,
+            imported_snapshot: None,
+            selected_server: None,
+            favorites: storage::load_favorites(),
+            _import_reader: None,
+            copy_status_handle: None,
+            server_names: None,
+            new_servers: HashSet::new(),
+            removed_servers: Vec::new(),
+            server_changes_handle: None,
+            group_voxel: false,
+            player_threshold: 100,
+            merge_map_variants: true,
+            filters,
+            is_loading: last_updated.is_none(),
+            is_cached: last_updated.is_some(),
+            is_fetching: false,
+            consecutive_failures: 0,
+            error: None,
+            is_rate_limited: false,
+            last_updated,
+            now: Date::now(),
+            _clock_handle: clock_handle,
+            is_offline: !window.navigator().on_line(),
+            _online_listener: online_listener,
+            _offline_listener: offline_listener,
+            is_hidden: document.hidden(),
+            _visibility_listener: visibility_listener,
+            history,
+            previous_snapshot: None,
+            server_context: ServerContext::default(),
+        };
+
+        if app.is_cached {
+            let filters = app.filters.clone();
+            app.drop_unknown_filters();
+
+            if app.filters != filters {
+                app.filters.write_to_url();
+            }
         }
+
+        app.refresh_server_context();
+        app
     }
 
     fn update(&mut self, ctx: &yew::prelude::Context<Self>, msg: Self::Message) -> bool {
-        match msg {
+        let previous_filters = self.filters.clone();
+
+        let render = match msg {
             Msg::UpdateData => {
-                ctx.link().send_future(async {
-                    let bbapi = BBApi::new();
+                if self.is_fetching || self.polling_paused() {
+                    return false;
+                }
 
-                    match bbapi.server_list().await {
-                        Ok(data) => Msg::Updated(data),
-                        Err(_) => Msg::UpdateFailed
-                    }
-                });
+                // Restart the cadence once this fetch completes.
+                self.stop_polling();
+                self.fetch(ctx);
 
-                false
+                true
+            },
+            Msg::Poll => {
+                if self.is_fetching || self.polling_paused() {
+                    return false;
+                }
+
+                self.fetch(ctx);
+
+                true
             },
             Msg::Updated(data) => {
+                let is_first_update = self.is_loading || self.is_cached;
+
+                self.previous_snapshot = (!self.is_loading).then(|| self.snapshot());
                 self.server_data = data;
 
+                if is_first_update {
+                    self.drop_unknown_filters();
+                }
+
+                self.is_loading = false;
+                self.is_cached = false;
+                self.is_fetching = false;
+                self.abort_controller = None;
+                self.consecutive_failures = 0;
+                self.error = None;
+                self.is_rate_limited = false;
+                self.now = Date::now();
+                self.last_updated = Some(self.now);
+                self.record_history();
+                self.track_server_changes(ctx);
+                self.refresh_server_context();
+                storage::save_servers(self.now, &self.server_data);
+
+                if self.poll_handle.is_none() && !self.polling_paused() {
+                    self.start_polling(ctx);
+                }
+
+                true
+            },
+            Msg::UpdateFailed(err) => {
+                self.is_fetching = false;
+                self.abort_controller = None;
+                self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+
+                let retry_delay = match err {
+                    FetchError::RateLimited(Some(secs)) => secs.saturating_mul(1000).clamp(RETRY_DELAY_MS, MAX_RETRY_AFTER_MS),
+                    FetchError::RateLimited(None) => self.retry_delay_ms().max(self.refresh_ms()),
+                    _ => self.retry_delay_ms(),
+                };
+
+                self.is_rate_limited = matches!(err, FetchError::RateLimited(_));
+
+                // The offline banner covers this, and WentOnline fetches again.
+                if err == FetchError::Offline {
+                    self.is_offline = true;
+                }
+
+                self.stop_polling();
+
+                let is_retrying = !self.polling_paused();
+                if is_retrying {
+                    self.schedule_next(ctx, retry_delay);
+                }
+
+                self.error = match err {
+                    FetchError::Offline => None,
+                    _ if is_retrying => Some(format!("{}, retrying in {}s…", err.message(), retry_delay / 1000)),
+                    _ => Some(format!("{}.", err.message())),
+                };
+
+                true
+            },
+            Msg::DismissError => {
+                self.error = None;
+                true
+            },
+            Msg::WentOnline => {
+                self.is_offline = false;
+                ctx.link().send_message(Msg::UpdateData);
+                true
+            },
+            Msg::WentOffline => {
+                self.is_offline = true;
+                self.stop_polling();
+
+                true
+            },
+            Msg::VisibilityChanged(visible) => {
+                self.is_hidden = !visible;
+
+                if visible {
+                    ctx.link().send_message(Msg::UpdateData);
+                } else {
+                    self.stop_polling();
+                }
+
+                false
+            },
+            Msg::IntervalChanged(refresh_secs) => {
+                self.refresh_secs = refresh_secs.max(MIN_REFRESH_SECS);
+                storage::save_refresh_secs(self.refresh_secs);
+
+                if self.poll_handle.is_some() {
+                    self.start_polling(ctx);
+                }
+
+                true
+            },
+            Msg::ThemeChanged(theme) => {
+                self.theme = theme;
+                storage::save_theme(self.theme.key());
+                true
+            },
+            Msg::DownloadCsv => {
+                download("battlebit-servers.csv", "text/csv", &servers_to_csv(&self.filtered_servers()));
+                false
+            },
+            Msg::DownloadJson => {
+                if let Ok(json) = serde_json::to_string_pretty(&self.stats_snapshot()) {
+                    download("battlebit-stats.json", "application/json", &json);
+                }
+
+                false
+            },
+            Msg::CopySummary => {
+                copy_to_clipboard(ctx, self.summary_text());
+                false
+            },
+            Msg::ShareView => {
+                let stats = self.stats();
+                let (players, queued) = stats.player_count();
+                let shared_view = SharedView {
+                    at: self.last_updated.unwrap_or(self.now),
+                    players,
+                    queued,
+                    servers: stats.server_count(),
+                };
+
+                let location = gloo::utils::window().location();
+                let url = format!(
+                    "{}{}#{}",
+                    location.origin().unwrap_or_default(),
+                    location.pathname().unwrap_or_default(),
+                    shared_view.to_fragment(&self.filters),
+                );
+
+                copy_to_clipboard(ctx, url);
+                false
+            },
+            Msg::ShareLink => {
+                let window = gloo::utils::window();
+                let url = window.location().href().unwrap_or_default();
+
+                if !self.can_share {
+                    copy_to_clipboard(ctx, url);
+                    return false;
+                }
+
+                let data = ShareData::new();
+                data.set_title(BASE_TITLE);
+                data.set_text(&format!("{} players are online in BattleBit right now", format_count(self.stats().player_count().0)));
+                data.set_url(&url);
+
+                // Rejects when the user dismisses the share sheet, which needs no handling.
+                let share = window.navigator().share_with_data(&data);
+                wasm_bindgen_futures::spawn_local(async move {
+                    let _ = JsFuture::from(share).await;
+                });
+
+                false
+            },
+            Msg::DismissSharedView => {
+                self.shared_view = None;
+                true
+            },
+            Msg::SnapshotFileSelected(file) => {
+                let reader = {
+                    let link = ctx.link().clone();
+                    gloo::file::callbacks::read_as_text(&file, move |contents| {
+                        let snapshot = contents
+                            .map_err(|err| format!("Could not read the file ({err})"))
+                            .and_then(|contents| {
+                                serde_json::from_str::<StatsSnapshot>(&contents)
+                                    .map_err(|_| String::from("This file is not an exported stats snapshot"))
+                            });
+
+                        link.send_message(Msg::SnapshotImported(snapshot))
+                    })
+                };
+
+                self._import_reader = Some(reader);
+
+                false
+            },
+            Msg::SnapshotImported(snapshot) => {
+                self.imported_snapshot = Some(snapshot);
+                self._import_reader = None;
+                true
+            },
+            Msg::ClearImportedSnapshot => {
+                self.imported_snapshot = None;
+                true
+            },
+            Msg::SelectServer(server) => {
+                self.selected_server = Some(server);
+                true
+            },
+            Msg::ToggleFavorite(name) => {
+                if !self.favorites.remove(&name) {
+                    self.favorites.insert(name);
+                }
+
+                storage::save_favorites(&self.favorites);
+                true
+            },
+            Msg::CloseModal => {
+                self.selected_server.take().is_some()
+            },
+            Msg::Copied(copied) => {
+                self.copy_status = Some(copied);
+
                 let handle = {
                     let link = ctx.link().clone();
-                    Timeout::new(60_000, move || link.send_message(Msg::UpdateData))
+                    Timeout::new(2_000, move || link.send_message(Msg::ClearCopyStatus))
                 };
 
-                self.timer_handle = Some(handle);
+                self.copy_status_handle = Some(handle);
 
                 true
             },
-            Msg::UpdateFailed => {
-                false
+            Msg::ClearCopyStatus => {
+                self.copy_status = None;
+                self.copy_status_handle = None;
+                true
+            },
+            Msg::ClearServerChanges => {
+                self.new_servers.clear();
+                self.removed_servers.clear();
+                self.server_changes_handle = None;
+                true
+            },
+            // Only the stale flag depends on the clock here, RelativeTime keeps the ages current itself.
+            Msg::Tick => {
+                let was_stale = self.is_stale();
+                self.now = Date::now();
+                self.is_stale() != was_stale
             },
+            Msg::ToggleVoxelGrouping => {
+                self.group_voxel = !self.group_voxel;
+                true
+            },
+            Msg::ThresholdChanged(threshold) => {
+                self.player_threshold = threshold;
+                true
+            },
+            Msg::ToggleMapVariants => {
+                self.merge_map_variants = !self.merge_map_variants;
+                self.filters.map = None;
+                self.refresh_server_context();
+                true
+            },
+            Msg::FilterChanged(filters) => {
+                self.filters = filters;
+                true
+            },
+        };
+
+        if self.filters != previous_filters {
+            self.filters.write_to_url();
+            self.previous_snapshot = None;
+            self.refresh_server_context();
+        }
+
+        render
+    }
+
+    fn rendered(&mut self, _ctx: &yew::prelude::Context<Self>, _first_render: bool) {
+        self.update_title();
+    }
+
+    fn destroy(&mut self, _ctx: &yew::prelude::Context<Self>) {
+        self.stop_polling();
+        gloo::utils::document().set_title(BASE_TITLE);
+
+        if let Some(controller) = self.abort_controller.take() {
+            controller.abort();
         }
     }
 
-    fn view(&self, _ctx: &yew::prelude::Context<Self>) -> Html {
-        let maps = self.map_count()
+    fn view(&self, ctx: &yew::prelude::Context<Self>) -> Html {
+        if let Some(format) = self.raw_format {
+            // Until the first fetch lands every figure would read as a real zero to scrapers.
+            let text = match format {
+                RawFormat::Prometheus if self.is_loading => String::from("# No data yet, the first fetch is still running.\n"),
+                RawFormat::Prometheus => self.stats_snapshot().to_prometheus(),
+                RawFormat::Json if self.is_loading => JsonFeed::pending(&String::from(Date::new_0().to_iso_string())).render(),
+                RawFormat::Json => self.stats_snapshot().to_json_feed(),
+            };
+
+            return html!{ <pre>{text}</pre> };
+        }
+
+        if self.embed {
+            return self.view_embed();
+        }
+
+        let stats = self.stats();
+        let maps_by_players = stats.maps_by_players();
+        let map_chart = maps_by_players.iter()
+            .map(|(item, _, players)| (item.clone(), *players))
+            .collect::<Vec<(String, usize)>>();
+        let map_servers = maps_by_players.into_iter()
+            .map(|(name, count, players)| CountItem { name, count, players: Some(players), ..Default::default() })
+            .collect::<Vec<CountItem>>();
+
+        let gamemodes_by_players = stats.gamemodes_by_players();
+        let gamemode_chart = gamemodes_by_players.iter()
+            .map(|(item, _, players)| (item.clone(), *players))
+            .collect::<Vec<(String, usize)>>();
+        let gamemode_servers = gamemodes_by_players.into_iter()
+            .map(|(name, count, players)| CountItem { name, count, players: Some(players), ..Default::default() })
+            .collect::<Vec<CountItem>>();
+
+        let region_colors = self.server_data.iter()
+            .map(|server| (region_to_string(server.region()), region_color(server.region())))
+            .collect::<HashMap<String, &'static str>>();
+        let region_chart = stats.region_count()
             .into_iter()
-            .sorted_by(|a, b| b.1.cmp(&a.1))
-            .map(|(item, count)| {
-                html!{ <> {format!("{item} ({count})")} <br/> </> }
-            })
-            .collect::<Vec<Html>>();
+            .sorted_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)))
+            .collect::<Vec<(String, usize)>>();
 
-        let gamemodes = self.gamemode_count()
+        let region_servers = stats.region_share()
+            .into_iter()
+            .map(|(region, count, share)| (region, (count, share)))
+            .collect::<HashMap<String, (usize, f64)>>();
+        let region_averages = stats.average_players_per_region();
+        let region_trends = self.region_trends(&stats);
+        let regions = stats.regions_by_players()
             .into_iter()
-            .sorted_by(|a, b| b.1.cmp(&a.1))
-            .map(|(item, count)| {
-                html!{ <> {format!("{item} ({count})")} <br/> </> }
+            .map(|(name, players)| {
+                let (count, share) = region_servers.get(&name).copied().unwrap_or((0, 0.0));
+                let average = region_averages.get(&name).copied().unwrap_or(0.0);
+                CountItem {
+                    trend: region_trends.as_ref().and_then(|trends| trends.get(&name).copied()),
+                    name,
+                    count,
+                    players: Some(players),
+                    share: Some(share),
+                    detail: Some(format!("avg {average:.0} players per server")),
+                }
             })
-            .collect::<Vec<Html>>();
+            .collect::<Vec<CountItem>>();
+
+        let most_queued_server = match stats.most_queued_server() {
+            Some(server) => html!{
+                <>
+                {server.name()}
+                <br/>
+                {format!("{} players waiting in the queue", format_count(*server.queued_player_count() as usize))}
+                </>
+            },
+            None => html!{ {"No servers online"} },
+        };
+
+        let busiest_region = match stats.busiest_region() {
+            Some((region, players)) => html!{
+                <p class="has-text-weight-bold">{format!("Most populated: {region} ({} players)", format_count(players))}</p>
+            },
+            None => html!{},
+        };
 
-        let regions = self.region_count()
+        let busiest_map = match stats.busiest_map() {
+            Some((map, players)) => html!{
+                <p class="has-text-weight-bold">{format!("Most populated: {map} ({} players)", format_count(players))}</p>
+            },
+            None => html!{},
+        };
+
+        let refresh_options = REFRESH_OPTIONS.into_iter()
+            .map(|(secs, label)| {
+                html!{ <option value={secs.to_string()} selected={self.refresh_secs == secs}>{format!("Every {label}")}</option> }
+            })
+            .collect::<Html>();
+
+        let error_message = self.error.as_ref().map(|error| {
+            let last_updated = self.last_updated
+                .map(|timestamp| format!(" Last successful update at {}.", Date::new(&JsValue::from_f64(timestamp)).to_locale_time_string("default")))
+                .unwrap_or_default();
+
+            format!("{error}{last_updated}")
+        });
+
+        let shared_notice = match &self.shared_view {
+            Some(shared_view) => html!{
+                <ybc::Notification classes={classes!("is-info")}>
+                    <button class="delete" onclick={ctx.link().callback(|_| Msg::DismissSharedView)}></button>
+                    {format!(
+                        "Shared view from {}: {} players ({} queued) across {} servers.",
+                        Date::new(&JsValue::from_f64(shared_view.at)).to_locale_string("default", &JsValue::UNDEFINED),
+                        format_count(shared_view.players),
+                        format_count(shared_view.queued),
+                        format_count(shared_view.servers),
+                    )}
+                </ybc::Notification>
+            },
+            None => html!{},
+        };
+
+        let copy_status = match self.copy_status {
+            Some(true) => html!{ <ybc::Control><ybc::Tag classes={classes!("is-success")}>{"Copied!"}</ybc::Tag></ybc::Control> },
+            Some(false) => html!{ <ybc::Control><ybc::Tag classes={classes!("is-danger")}>{"Clipboard access was denied"}</ybc::Tag></ybc::Control> },
+            None => html!{},
+        };
+
+        let snapshot_comparison = match &self.imported_snapshot {
+            Some(Ok(saved)) => {
+                let (players, queued) = stats.player_count();
+                let rows = [
+                    ("Players", saved.players, players),
+                    ("Queued", saved.queued, queued),
+                    ("Servers", saved.servers, stats.server_count()),
+                ];
+
+                html!{
+                    <ybc::Box>
+                        <button class="delete is-pulled-right" onclick={ctx.link().callback(|_| Msg::ClearImportedSnapshot)}></button>
+                        <p class="mb-2">{format!("Compared to your snapshot from {}", saved.updated_at.as_deref().unwrap_or(&saved.generated_at))}</p>
+                        <table class="table is-narrow">
+                            <thead>
+                                <tr><th></th><th>{"Saved"}</th><th>{"Live"}</th><th>{"Change"}</th></tr>
+                            </thead>
+                            <tbody>
+                            { for rows.into_iter().map(|(label, saved, live)| html!{
+                                <tr>
+                                    <th>{label}</th>
+                                    <td>{format_count(saved)}</td>
+                                    <td>{format_count(live)}</td>
+                                    <td>{format_delta(live as i64 - saved as i64)}</td>
+                                </tr>
+                            }) }
+                            </tbody>
+                        </table>
+                    </ybc::Box>
+                }
+            },
+            Some(Err(error)) => html!{
+                <ybc::Notification classes={classes!("is-danger")}>
+                    <button class="delete" onclick={ctx.link().callback(|_| Msg::ClearImportedSnapshot)}></button>
+                    {error}
+                </ybc::Notification>
+            },
+            None => html!{},
+        };
+
+        let on_snapshot_file = ctx.link().batch_callback(|event: Event| {
+            let input = event.target_dyn_into::<HtmlInputElement>()?;
+            let file = input.files()?.get(0)?;
+            input.set_value("");
+
+            Some(Msg::SnapshotFileSelected(gloo::file::File::from(file)))
+        });
+
+        let active_filters = self.filters.active_count();
+        let global_players = (active_filters > 0).then(|| {
+            let (active, queued) = self.global_player_count();
+            (active, queued, self.server_data.len())
+        });
+
+        let population = stats.population_histogram(HISTOGRAM_BUCKET_SIZE)
             .into_iter()
-            .sorted_by(|a, b| b.1.cmp(&a.1))
-            .map(|(item, count)| {
-                html!{<>{format!("{item} ({count})")}<br/></>}
+            .map(|(bucket, count)| {
+                html!{ <> {format!("{}–{} players: {} servers", bucket, bucket + HISTOGRAM_BUCKET_SIZE - 1, format_count(count))} <br/> </> }
             })
             .collect::<Vec<Html>>();
 
-        let player_count = self.player_count();
+        let player_count = stats.player_count();
+        let trends = self.trends(&stats);
+        let average_players = stats.average_players_per_server();
+        let median_players = stats.median_player_count();
+        let std_dev = stats.player_count_std_dev();
+        let server_count = stats.server_count();
+        let fill_rate = stats.fill_rate();
+        let queue_ratio = stats.queue_ratio();
+        let empty_servers = stats.empty_server_count();
+        let full_servers = stats.full_server_count();
+        let servers_above = stats.servers_above(self.player_threshold);
+        let on_threshold = ctx.link().batch_callback(|e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            input.value().parse().ok().map(Msg::ThresholdChanged)
+        });
+
+        let busiest_server = match stats.busiest_server() {
+            Some(server) => html!{
+                <>
+                {server.name()}
+                <br/>
+                {format!("{} players on {}", server.player_count(), server.map())}
+                </>
+            },
+            None => html!{ {"No servers online"} },
+        };
 
         html! {
             <>
@@ -164,49 +1479,229 @@ impl Component for App {
                         <ybc::Title classes={classes!("has-text-white")} size={ybc::HeaderSize::Is4}>{"Battlebit Server Statistics"}</ybc::Title>
                     </ybc::NavbarItem>
                 }}
+                navend={html!{
+                    <>
+                    if let Some(timestamp) = self.last_updated {
+                        <ybc::NavbarItem classes={classes!("has-text-white")}>
+                            {"Updated "}<RelativeTime {timestamp} />
+                            if self.is_cached {
+                                <ybc::Tag classes={classes!("is-info", "ml-2")}>{"cached from "}<RelativeTime {timestamp} /></ybc::Tag>
+                            } else if self.is_stale() {
+                                <ybc::Tag classes={classes!("is-warning", "ml-2")}>{"data may be outdated"}</ybc::Tag>
+                            }
+                        </ybc::NavbarItem>
+                    }
+                    <ybc::NavbarItem>
+                        <ybc::Select
+                            name="refresh_interval"
+                            classes={classes!("is-small")}
+                            value={self.refresh_secs.to_string()}
+                            update={ctx.link().batch_callback(|value: String| value.parse().ok().map(Msg::IntervalChanged))}
+                        >
+                            { refresh_options }
+                        </ybc::Select>
+                    </ybc::NavbarItem>
+                    <ybc::NavbarItem>
+                        <ThemeToggle theme={self.theme} onchange={ctx.link().callback(Msg::ThemeChanged)} />
+                    </ybc::NavbarItem>
+                    <ybc::NavbarItem>
+                        <ybc::Button
+                            classes={classes!("is-primary", "is-inverted", "is-outlined")}
+                            loading={self.is_fetching}
+                            disabled={self.is_fetching}
+                            onclick={ctx.link().callback(|_| Msg::UpdateData)}
+                        >
+                            {"Refresh now"}
+                        </ybc::Button>
+                    </ybc::NavbarItem>
+                    </>
+                }}
                 navburger=false
             />
 
-            <ybc::Hero
-                classes={classes!("is-dark")}
-                size={ybc::HeroSize::FullheightWithNavbar}
-                body={html!{    
-                    <ybc::Tile ctx={Ancestor}>
-                        <ybc::Tile ctx={Parent} size={ybc::TileSize::Twelve}>
-                            <ybc::Tile ctx={Parent}>
-                                <ybc::Tile ctx={Child} classes={classes!("notification", "is-primary")}>
-                                    <ybc::Subtitle size={ybc::HeaderSize::Is3} classes={classes!("has-text-white", "mb-0")}>{"Players"}</ybc::Subtitle>
-                                    {format!("{} are playing, with another {} in the queue. ", player_count.0, player_count.1)}
+            <ContextProvider<Theme> context={self.theme}>
+            <ContextProvider<ServerContext> context={self.server_context.clone()}>
+                <ybc::Hero
+                    classes={classes!(self.theme.hero_class())}
+                    size={ybc::HeroSize::FullheightWithNavbar}
+                    body={html!{
+                        <>
+                        <ErrorBanner
+                            message={self.is_offline.then(|| String::from("You appear to be offline. Updates are paused until your connection is back."))}
+                            color="is-warning"
+                        />
+                        <ErrorBanner
+                            message={error_message}
+                            color={if self.is_rate_limited { "is-warning" } else { "is-danger" }}
+                            ondismiss={ctx.link().callback(|_| Msg::DismissError)}
+                        />
+                        { shared_notice }
+                        if self.is_loading {
+                            <div class="has-text-centered">
+                                <progress class="progress is-small is-primary" max="100"></progress>
+                                {"Loading server list…"}
+                            </div>
+                        } else if self.server_data.is_empty() {
+                            <ybc::Box classes={classes!("has-text-centered")}>
+                                <ybc::Title size={ybc::HeaderSize::Is4}>{"No servers are currently online"}</ybc::Title>
+                                <ybc::Subtitle size={ybc::HeaderSize::Is6}>{"The server list will refresh automatically once servers are back."}</ybc::Subtitle>
+                            </ybc::Box>
+                        } else {
+                            <FilterBar
+                                filters={self.filters.clone()}
+                                regions={self.available_regions()}
+                                gamemodes={self.available_gamemodes()}
+                                maps={self.available_maps()}
+                                anti_cheats={self.available_anti_cheats()}
+                                builds={self.available_builds()}
+                                onchange={ctx.link().callback(Msg::FilterChanged)}
+                            />
+                            <ybc::Field grouped=true multiline=true classes={classes!("mb-5")}>
+                                <ybc::Control>
+                                    {format!("Showing {} of {} servers", format_count(server_count), format_count(self.server_data.len()))}
+                                </ybc::Control>
+                                <ybc::Control>
+                                    <ybc::Button classes={classes!("is-small")} onclick={ctx.link().callback(|_| Msg::DownloadCsv)}>
+                                        {"Download CSV"}
+                                    </ybc::Button>
+                                </ybc::Control>
+                                <ybc::Control>
+                                    <ybc::Button classes={classes!("is-small")} onclick={ctx.link().callback(|_| Msg::DownloadJson)}>
+                                        {"Download JSON"}
+                                    </ybc::Button>
+                                </ybc::Control>
+                                <ybc::Control>
+                                    <ybc::Button classes={classes!("is-small")} onclick={ctx.link().callback(|_| Msg::CopySummary)}>
+                                        {"Copy summary"}
+                                    </ybc::Button>
+                                </ybc::Control>
+                                <ybc::Control>
+                                    <ybc::Button classes={classes!("is-small")} onclick={ctx.link().callback(|_| Msg::ShareView)}>
+                                        {"Share this view"}
+                                    </ybc::Button>
+                                </ybc::Control>
+                                <ybc::Control>
+                                    <ybc::Button classes={classes!("is-small")} onclick={ctx.link().callback(|_| Msg::ShareLink)}>
+                                        { if self.can_share { "Share" } else { "Copy link" } }
+                                    </ybc::Button>
+                                </ybc::Control>
+                                <ybc::Control>
+                                    <div class="file is-small">
+                                        <label class="file-label">
+                                            <input class="file-input" type="file" accept="application/json,.json" onchange={on_snapshot_file} />
+                                            <span class="file-cta">
+                                                <span class="file-label">{"Import snapshot"}</span>
+                                            </span>
+                                        </label>
+                                    </div>
+                                </ybc::Control>
+                                { copy_status }
+                            </ybc::Field>
+                            { snapshot_comparison }
+                            <ybc::Tile ctx={Ancestor} classes={classes!(self.is_stale().then_some("is-stale"))}>
+                                <ybc::Tile ctx={Parent} size={ybc::TileSize::Twelve}>
+                                    <StatTile title="Players">
+                                        <PlayerSummary
+                                            players={player_count}
+                                            trends={trends.map(|trends| (trends.0, trends.1))}
+                                            history={self.recent_history(SPARKLINE_POINTS)}
+                                            global={global_players}
+                                            peak={self.peak_players().map(|peak| (self.history().len(), peak))}
+                                            average={average_players}
+                                            median={median_players}
+                                            servers={server_count}
+                                            fill_rate={fill_rate}
+                                            queue_ratio={queue_ratio}
+                                        />
+                                    </StatTile>
+                                    <StatTile title="Distribution">
+                                        {format!("Mean: {:.1}", average_players)}
+                                        <br/>
+                                        {format!("Median: {}", median_players)}
+                                        <br/>
+                                        {format!("Std. deviation: {:.1}", std_dev)}
+                                    </StatTile>
+                                    <StatTile title="Population">
+                                        { population }
+                                    </StatTile>
+                                    <StatTile title="Servers">
+                                        {format!("{} servers online now", format_count(server_count))}
+                                        { trend_arrow(trends.map(|trends| trends.2)) }
+                                    </StatTile>
+                                    <StatTile title="Occupancy">
+                                        {format!("{} empty", format_count(empty_servers))}
+                                        <br/>
+                                        {format!("{} full", format_count(full_servers))}
+                                        <br/>
+                                        {format!("{} servers with {}+ players", format_count(servers_above), self.player_threshold)}
+                                        <input
+                                            class="input is-small"
+                                            type="number"
+                                            min="0"
+                                            value={self.player_threshold.to_string()}
+                                            oninput={on_threshold}
+                                        />
+                                    </StatTile>
+                                    <StatTile title="Hottest server right now">
+                                        { busiest_server }
+                                    </StatTile>
+                                    <StatTile title="Longest queue">
+                                        { most_queued_server }
+                                    </StatTile>
+                                    <StatTile title="Regions">
+                                        { busiest_region }
+                                        <CountList items={regions} />
+                                        <DonutChart items={region_chart} colors={region_colors} />
+                                    </StatTile>
+                                    <StatTile title="Gamemodes">
+                                        <CountList items={gamemode_servers} max_items={DEFAULT_TOP_N} />
+                                        <BarChart items={gamemode_chart} />
+                                        <ybc::Checkbox
+                                            name="group_voxel"
+                                            checked={self.group_voxel}
+                                            update={ctx.link().callback(|_| Msg::ToggleVoxelGrouping)}
+                                        >
+                                            {" Group voxel modes"}
+                                        </ybc::Checkbox>
+                                    </StatTile>
+                                    <StatTile title="Maps">
+                                        { busiest_map }
+                                        <CountList items={map_servers} max_items={DEFAULT_TOP_N} />
+                                        <BarChart items={map_chart} max_items={MAP_CHART_ITEMS} />
+                                        <ybc::Checkbox
+                                            name="merge_map_variants"
+                                            checked={self.merge_map_variants}
+                                            update={ctx.link().callback(|_| Msg::ToggleMapVariants)}
+                                        >
+                                            {" Merge day/night variants"}
+                                        </ybc::Checkbox>
+                                    </StatTile>
                                 </ybc::Tile>
                             </ybc::Tile>
-                            <ybc::Tile ctx={Parent}>
-                                <ybc::Tile ctx={Child} classes={classes!("notification", "is-primary")}>
-                                    <ybc::Subtitle size={ybc::HeaderSize::Is3} classes={classes!("has-text-white", "mb-0")}>{"Regions"}</ybc::Subtitle>
-                                    { regions }
-                                </ybc::Tile>
-                            </ybc::Tile>
-                            <ybc::Tile ctx={Parent}>
-                                <ybc::Tile ctx={Child} classes={classes!("notification", "is-primary")}>
-                                    <ybc::Subtitle size={ybc::HeaderSize::Is3} classes={classes!("has-text-white", "mb-0")}>{"Gamemodes"}</ybc::Subtitle>
-                                    { gamemodes }
-                                </ybc::Tile>
-                            </ybc::Tile>
-                            <ybc::Tile ctx={Parent}>
-                                <ybc::Tile ctx={Child} classes={classes!("notification", "is-primary")}>
-                                    <ybc::Subtitle size={ybc::HeaderSize::Is3} classes={classes!("has-text-white", "mb-0")}>{"Maps"}</ybc::Subtitle>
-                                    { maps }
-                                </ybc::Tile>
-                            </ybc::Tile>
-                        </ybc::Tile>
-                    </ybc::Tile>
-                }}
-                foot={html!{
-                    <ybc::Subtitle size={ybc::HeaderSize::Is6} classes={classes!("has-text-white")}>
-                    {"Made with ♥︎ by "} <a href="https://github.com/superyu1337">{"Superyu1337"}</a>
-                    </ybc::Subtitle>
-                }}
-                foot_classes={classes!("is-primary", "content", "has-text-centered")}
-            />
+                            <ServerTable
+                                onselect={ctx.link().callback(Msg::SelectServer)}
+                                favorites={self.favorites.clone()}
+                                offline_favorites={self.offline_favorites()}
+                                ontogglefavorite={ctx.link().callback(Msg::ToggleFavorite)}
+                                new_servers={self.new_servers.clone()}
+                                removed_servers={self.removed_servers.clone()}
+                            />
+                        }
+                        </>
+                    }}
+                    foot={html!{
+                        <ybc::Subtitle size={ybc::HeaderSize::Is6} classes={classes!("has-text-white")}>
+                        {"Made with ♥︎ by "} <a href="https://github.com/superyu1337">{"Superyu1337"}</a>
+                        </ybc::Subtitle>
+                    }}
+                    foot_classes={classes!("is-primary", "content", "has-text-centered")}
+                />
+            </ContextProvider<ServerContext>>
+            </ContextProvider<Theme>>
+
+            if let Some(server) = &self.selected_server {
+                <ServerDetails server={server.clone()} onclose={ctx.link().callback(|_| Msg::CloseModal)} />
+            }
             </>
         }
     }
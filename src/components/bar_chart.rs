@@ -0,0 +1,60 @@
+use itertools::Itertools;
+use yew::prelude::*;
+
+use crate::format::format_count;
+
+use super::stable_color;
+
+#[derive(Properties, PartialEq)]
+pub struct BarChartProps {
+    pub items: Vec<(String, usize)>,
+    /// Entries past this are summed into a single "Others" bar.
+    #[prop_or_default]
+    pub max_items: Option<usize>,
+}
+
+#[function_component(BarChart)]
+pub fn bar_chart(props: &BarChartProps) -> Html {
+    let mut items = props.items.iter()
+        .cloned()
+        .sorted_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)))
+        .collect::<Vec<(String, usize)>>();
+
+    if let Some(max_items) = props.max_items {
+        if items.len() > max_items {
+            let others = items.split_off(max_items).into_iter().map(|(_, count)| count).sum();
+            items.push((String::from("Others"), others));
+        }
+    }
+
+    let max = items.iter().map(|(_, count)| *count).max().unwrap_or(0);
+
+    if max == 0 {
+        return html!{};
+    }
+
+    let bars = items.iter()
+        .map(|(label, count)| {
+            let width = *count as f64 / max as f64 * 100.0;
+
+            html!{
+                <div key={label.clone()} class="bar-chart-row">
+                    <span class="bar-chart-label is-size-7">{label}</span>
+                    <div class="bar-chart-track">
+                        <div
+                            class="bar-chart-bar"
+                            style={format!("width: {width:.1}%; background: {};", stable_color(label))}
+                        ></div>
+                    </div>
+                    <span class="bar-chart-count is-size-7">{format_count(*count)}</span>
+                </div>
+            }
+        })
+        .collect::<Html>();
+
+    html!{
+        <div class="bar-chart mt-3">
+            { bars }
+        </div>
+    }
+}
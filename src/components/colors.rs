@@ -0,0 +1,40 @@
+use battlebit_api::Region;
+
+const PALETTE: [&str; 10] = [
+    "#4e79a7", "#f28e2b", "#e15759", "#76b7b2", "#59a14f",
+    "#edc948", "#b07aa1", "#ff9da7", "#9c755f", "#bab0ac",
+];
+
+/// A fixed, distinct color per region, hashing could give two regions the same one.
+pub fn region_color(region: &Region) -> &'static str {
+    match region {
+        Region::Europe => "#4e79a7",
+        Region::America => "#f28e2b",
+        Region::Japan => "#e15759",
+        Region::Australia => "#59a14f",
+        Region::Brazil => "#edc948",
+        Region::Asia => "#b07aa1",
+        Region::DeveloperServer => "#9c755f",
+        Region::Unknown => "#bab0ac",
+    }
+}
+
+/// Picks a palette color based on the label alone, for open-ended labels like maps,
+/// so an entry keeps its color no matter where it ends up in a sorted list.
+pub fn stable_color(label: &str) -> &'static str {
+    // FNV-1a, stable across builds unlike the std hasher.
+    let hash = label.bytes().fold(0x811c9dc5u32, |hash, byte| {
+        (hash ^ byte as u32).wrapping_mul(0x01000193)
+    });
+
+    PALETTE[hash as usize % PALETTE.len()]
+}
+
+/// Bulma color class for how full something is, given as a percentage.
+pub fn fill_color(fill_rate: f64) -> &'static str {
+    match fill_rate {
+        rate if rate >= 90.0 => "is-danger",
+        rate if rate >= 60.0 => "is-warning",
+        _ => "is-success",
+    }
+}
@@ -0,0 +1,121 @@
+use yew::prelude::*;
+
+use crate::format::format_count;
+
+use super::trend_arrow;
+
+/// An entry of a ranked list. Everything but the name and count is optional.
+#[derive(Clone, PartialEq, Default)]
+pub struct CountItem {
+    pub name: String,
+    pub count: usize,
+    /// Players on those servers, shown after the count.
+    pub players: Option<usize>,
+    /// Percentage of all servers, shown after the count.
+    pub share: Option<f64>,
+    pub trend: Option<i64>,
+    /// Rendered below the entry in small print.
+    pub detail: Option<String>,
+}
+
+impl CountItem {
+    fn label(&self, unit: &str) -> String {
+        let mut label = format!("{} ({} {unit}", self.name, format_count(self.count));
+
+        if let Some(share) = self.share {
+            label.push_str(&format!(", {share}%"));
+        }
+
+        if let Some(players) = self.players {
+            label.push_str(&format!(", {} players", format_count(players)));
+        }
+
+        label.push(')');
+        label
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct CountListProps {
+    /// Already sorted entries, rendered in the given order.
+    pub items: Vec<CountItem>,
+    #[prop_or(AttrValue::Static("servers"))]
+    pub unit: AttrValue,
+    /// Entries past this are summarized as "…and N more".
+    #[prop_or_default]
+    pub max_items: Option<usize>,
+}
+
+#[function_component(CountList)]
+pub fn count_list(props: &CountListProps) -> Html {
+    let shown = props.max_items.unwrap_or(props.items.len()).min(props.items.len());
+    let hidden = props.items.len() - shown;
+
+    let lines = props.items[..shown].iter()
+        .map(|item| html!{
+            <>
+            {item.label(&props.unit)}
+            { trend_arrow(item.trend) }
+            <br/>
+            if let Some(detail) = &item.detail {
+                <span class="is-size-7">{detail}</span>
+                <br/>
+            }
+            </>
+        })
+        .collect::<Html>();
+
+    html!{
+        <>
+        { lines }
+        if hidden > 0 {
+            {format!("…and {} more", format_count(hidden))}
+            <br/>
+        }
+        </>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(items: Vec<CountItem>, max_items: Option<usize>) -> String {
+        let renderer = yew::ServerRenderer::<CountList>::with_props(move || CountListProps {
+            items,
+            unit: AttrValue::Static("servers"),
+            max_items,
+        })
+        .hydratable(false);
+
+        futures::executor::block_on(renderer.render())
+    }
+
+    fn item(name: &str, count: usize, players: usize) -> CountItem {
+        CountItem { name: name.to_owned(), count, players: Some(players), ..Default::default() }
+    }
+
+    #[test]
+    fn renders_nothing_without_items() {
+        assert_eq!(render(Vec::new(), Some(3)), "");
+    }
+
+    #[test]
+    fn shows_servers_and_players() {
+        let html = render(vec![item("Valley", 14, 1203)], None);
+
+        assert!(html.contains("Valley (14 servers, 1,203 players)"));
+        assert!(!html.contains("more"));
+    }
+
+    #[test]
+    fn summarizes_truncated_items() {
+        let items = vec![item("Valley", 14, 1203), item("Wakistan", 9, 800), item("Frugis", 3, 40)];
+        let html = render(items, Some(2));
+
+        assert!(html.contains("Valley"));
+        assert!(html.contains("Wakistan"));
+        assert!(!html.contains("Frugis"));
+        assert!(html.contains("…and 1 more"));
+    }
+}
@@ -0,0 +1,75 @@
+use std::collections::HashMap;
+
+use yew::prelude::*;
+
+use crate::format::format_count;
+
+use super::stable_color;
+
+/// Radius for which the circumference is exactly 100, so dash lengths are percentages.
+const RADIUS: f64 = 15.915_494_309_189_533;
+
+#[derive(Properties, PartialEq)]
+pub struct DonutChartProps {
+    pub items: Vec<(String, usize)>,
+    /// Fixed colors by label, other labels get their `stable_color`.
+    #[prop_or_default]
+    pub colors: HashMap<String, &'static str>,
+}
+
+#[function_component(DonutChart)]
+pub fn donut_chart(props: &DonutChartProps) -> Html {
+    let total = props.items.iter().map(|(_, count)| count).sum::<usize>();
+
+    if total == 0 {
+        return html!{};
+    }
+
+    let color = |label: &str| props.colors.get(label).copied().unwrap_or_else(|| stable_color(label));
+
+    let mut offset = 0.0;
+    let segments = props.items.iter()
+        .map(|(label, count)| {
+            let share = *count as f64 / total as f64 * 100.0;
+            let segment = html!{
+                <circle
+                    cx="21"
+                    cy="21"
+                    r={RADIUS.to_string()}
+                    fill="transparent"
+                    stroke={color(label)}
+                    stroke-width="6"
+                    stroke-dasharray={format!("{share} {}", 100.0 - share)}
+                    stroke-dashoffset={(25.0 - offset).to_string()}
+                >
+                    <title>{format!("{label}: {}", format_count(*count))}</title>
+                </circle>
+            };
+
+            offset += share;
+            segment
+        })
+        .collect::<Html>();
+
+    let legend = props.items.iter()
+        .map(|(label, count)| {
+            html!{
+                <li>
+                    <span style={format!("display: inline-block; width: 0.75em; height: 0.75em; margin-right: 0.5em; background: {};", color(label))}></span>
+                    {format!("{label} ({})", format_count(*count))}
+                </li>
+            }
+        })
+        .collect::<Html>();
+
+    html!{
+        <div class="mt-3">
+            <svg viewBox="0 0 42 42" width="160" height="160">
+                { segments }
+            </svg>
+            <ul class="is-size-7">
+                { legend }
+            </ul>
+        </div>
+    }
+}
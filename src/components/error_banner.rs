@@ -0,0 +1,53 @@
+use gloo::timers::callback::Timeout;
+use yew::prelude::*;
+
+/// Matches the transition duration of `.error-banner` in index.scss.
+const LEAVE_DURATION_MS: u32 = 300;
+
+#[derive(Properties, PartialEq)]
+pub struct ErrorBannerProps {
+    pub message: Option<String>,
+    #[prop_or(AttrValue::Static("is-danger"))]
+    pub color: AttrValue,
+    #[prop_or_default]
+    pub ondismiss: Option<Callback<()>>,
+}
+
+/// A notification shown while `message` is set. Clearing the message fades the
+/// last one out instead of removing it right away.
+#[function_component(ErrorBanner)]
+pub fn error_banner(props: &ErrorBannerProps) -> Html {
+    let last_message = use_state(|| props.message.clone());
+
+    {
+        let last_message = last_message.clone();
+        use_effect_with_deps(move |message| {
+            let timeout = match message {
+                Some(_) => {
+                    last_message.set(message.clone());
+                    None
+                },
+                None => Some(Timeout::new(LEAVE_DURATION_MS, move || last_message.set(None))),
+            };
+
+            move || drop(timeout)
+        }, props.message.clone());
+    }
+
+    let Some(message) = props.message.as_ref().or(last_message.as_ref()) else {
+        return html!{};
+    };
+
+    let leaving = props.message.is_none().then_some("is-leaving");
+    let dismiss = props.ondismiss.as_ref().map(|ondismiss| {
+        let onclick = ondismiss.reform(|_: MouseEvent| ());
+        html!{ <button class="delete" {onclick}></button> }
+    });
+
+    html!{
+        <div class={classes!("notification", "error-banner", props.color.to_string(), leaving)}>
+            { dismiss }
+            { message }
+        </div>
+    }
+}
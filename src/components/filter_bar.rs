@@ -0,0 +1,188 @@
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::filter::{anti_cheat_from_string, anti_cheat_to_string, FilterState, ServerType};
+use super::Theme;
+
+#[derive(Properties, PartialEq)]
+pub struct FilterBarProps {
+    pub filters: FilterState,
+    pub regions: Vec<String>,
+    pub gamemodes: Vec<String>,
+    pub maps: Vec<String>,
+    pub anti_cheats: Vec<String>,
+    pub builds: Vec<String>,
+    pub onchange: Callback<FilterState>,
+}
+
+fn select_options(all_label: &str, options: &[String], selected: &Option<String>) -> Html {
+    let options = options.iter()
+        .map(|option| {
+            let is_selected = selected.as_ref() == Some(option);
+            html!{ <option value={option.clone()} selected={is_selected}>{option}</option> }
+        })
+        .collect::<Html>();
+
+    html!{
+        <>
+        <option value="" selected={selected.is_none()}>{all_label}</option>
+        { options }
+        </>
+    }
+}
+
+/// Builds a callback that applies `apply` to a copy of the current filters and emits the result.
+fn change<T: 'static>(props: &FilterBarProps, apply: impl Fn(&mut FilterState, T) + 'static) -> Callback<T> {
+    let filters = props.filters.clone();
+    let onchange = props.onchange.clone();
+
+    Callback::from(move |value: T| {
+        let mut filters = filters.clone();
+        apply(&mut filters, value);
+        onchange.emit(filters);
+    })
+}
+
+fn non_empty(value: String) -> Option<String> {
+    (!value.is_empty()).then_some(value)
+}
+
+#[function_component(FilterBar)]
+pub fn filter_bar(props: &FilterBarProps) -> Html {
+    let theme = use_context::<Theme>().unwrap_or_default();
+    let filters = &props.filters;
+
+    let selected_anti_cheat = filters.anti_cheat.map(|anti_cheat| anti_cheat_to_string(&anti_cheat));
+    let server_types = ServerType::ALL.into_iter()
+        .map(|server_type| {
+            let selected = filters.server_type == server_type;
+            html!{
+                <ybc::Button
+                    classes={classes!(selected.then_some("is-info"), selected.then_some("is-selected"))}
+                    onclick={change(props, move |filters, _: MouseEvent| filters.server_type = server_type)}
+                >
+                    {server_type.label()}
+                </ybc::Button>
+            }
+        })
+        .collect::<Html>();
+
+    let on_min_players = {
+        let onchange = change(props, |filters, min_players| filters.min_players = min_players);
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+
+            if let Ok(min_players) = input.value().parse() {
+                onchange.emit(min_players);
+            }
+        })
+    };
+
+    let active_filters = filters.active_count();
+    let clear_filters = if active_filters > 0 {
+        html!{
+            <ybc::Control>
+                <ybc::Tag classes={classes!("is-warning", "mr-2")}>
+                    {format!("{} filter{} active", active_filters, if active_filters == 1 { "" } else { "s" })}
+                </ybc::Tag>
+                <ybc::Button classes={classes!("is-small")} onclick={change(props, |filters, _: MouseEvent| *filters = FilterState::default())}>
+                    {"Clear all filters"}
+                </ybc::Button>
+            </ybc::Control>
+        }
+    } else {
+        html!{}
+    };
+
+    html!{
+        <ybc::Field grouped=true multiline=true classes={classes!("mb-3")}>
+            <ybc::Control expanded=true>
+                <ybc::Input
+                    name="search"
+                    value={filters.search.clone()}
+                    update={change(props, |filters, search| filters.search = search)}
+                    placeholder="Search servers by name"
+                />
+            </ybc::Control>
+            <ybc::Control>
+                <ybc::Select
+                    name="region"
+                    value={filters.region.clone().unwrap_or_default()}
+                    update={change(props, |filters, region| filters.region = non_empty(region))}
+                >
+                    { select_options("All regions", &props.regions, &filters.region) }
+                </ybc::Select>
+            </ybc::Control>
+            <ybc::Control>
+                <ybc::Select
+                    name="gamemode"
+                    value={filters.gamemode.clone().unwrap_or_default()}
+                    update={change(props, |filters, gamemode| filters.gamemode = non_empty(gamemode))}
+                >
+                    { select_options("All gamemodes", &props.gamemodes, &filters.gamemode) }
+                </ybc::Select>
+            </ybc::Control>
+            <ybc::Control>
+                <ybc::Select
+                    name="map"
+                    value={filters.map.clone().unwrap_or_default()}
+                    update={change(props, |filters, map| filters.map = non_empty(map))}
+                >
+                    { select_options("All maps", &props.maps, &filters.map) }
+                </ybc::Select>
+            </ybc::Control>
+            <ybc::Control>
+                <ybc::Select
+                    name="anti_cheat"
+                    value={selected_anti_cheat.clone().unwrap_or_default()}
+                    update={change(props, |filters, anti_cheat: String| filters.anti_cheat = anti_cheat_from_string(&anti_cheat))}
+                >
+                    { select_options("All anti-cheats", &props.anti_cheats, &selected_anti_cheat) }
+                </ybc::Select>
+            </ybc::Control>
+            <ybc::Control>
+                <ybc::Select
+                    name="build"
+                    value={filters.build.clone().unwrap_or_default()}
+                    update={change(props, |filters, build| filters.build = non_empty(build))}
+                >
+                    { select_options("All versions", &props.builds, &filters.build) }
+                </ybc::Select>
+            </ybc::Control>
+            <ybc::Control>
+                <ybc::Buttons classes={classes!("has-addons")}>
+                    { server_types }
+                </ybc::Buttons>
+            </ybc::Control>
+            <ybc::Control>
+                <label class={classes!("label", theme.text_class(), "is-small", "mb-0")}>{format!("Min. players: {}", filters.min_players)}</label>
+                <input
+                    type="range"
+                    min="0"
+                    max="254"
+                    value={filters.min_players.to_string()}
+                    oninput={on_min_players}
+                />
+            </ybc::Control>
+            <ybc::Control>
+                <ybc::Checkbox
+                    name="hide_empty"
+                    checked={filters.hide_empty}
+                    update={change(props, |filters, hide_empty| filters.hide_empty = hide_empty)}
+                >
+                    {" Hide empty servers"}
+                </ybc::Checkbox>
+            </ybc::Control>
+            <ybc::Control>
+                <ybc::Checkbox
+                    name="hide_full"
+                    checked={filters.hide_full}
+                    update={change(props, |filters, hide_full| filters.hide_full = hide_full)}
+                >
+                    {" Hide full servers"}
+                </ybc::Checkbox>
+            </ybc::Control>
+            { clear_filters }
+        </ybc::Field>
+    }
+}
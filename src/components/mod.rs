@@ -0,0 +1,29 @@
+mod bar_chart;
+mod colors;
+mod count_list;
+mod donut_chart;
+mod error_banner;
+mod filter_bar;
+mod player_summary;
+mod relative_time;
+mod server_details;
+mod server_table;
+mod sparkline;
+mod stat_tile;
+mod theme_toggle;
+mod trend_arrow;
+
+pub use bar_chart::BarChart;
+pub use colors::{fill_color, region_color, stable_color};
+pub use count_list::{CountItem, CountList};
+pub use donut_chart::DonutChart;
+pub use error_banner::ErrorBanner;
+pub use filter_bar::FilterBar;
+pub use player_summary::PlayerSummary;
+pub use relative_time::RelativeTime;
+pub use server_details::ServerDetails;
+pub use server_table::ServerTable;
+pub use sparkline::Sparkline;
+pub use stat_tile::StatTile;
+pub use theme_toggle::{Theme, ThemeToggle};
+pub use trend_arrow::trend_arrow;
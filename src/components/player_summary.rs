@@ -0,0 +1,62 @@
+use yew::prelude::*;
+
+use crate::format::format_count;
+
+use super::{fill_color, trend_arrow, Sparkline};
+
+#[derive(Properties, PartialEq)]
+pub struct PlayerSummaryProps {
+    /// Active and queued players.
+    pub players: (usize, usize),
+    /// Change of active and queued players since the last update.
+    #[prop_or_default]
+    pub trends: Option<(i64, i64)>,
+    #[prop_or_default]
+    pub history: Vec<usize>,
+    /// Active players, queued players and servers before filtering, shown when filters are active.
+    #[prop_or_default]
+    pub global: Option<(usize, usize, usize)>,
+    /// Number of recorded updates and the highest player count among them.
+    #[prop_or_default]
+    pub peak: Option<(usize, usize)>,
+    pub average: f64,
+    pub median: u32,
+    pub servers: usize,
+    pub fill_rate: f64,
+    pub queue_ratio: f64,
+}
+
+#[function_component(PlayerSummary)]
+pub fn player_summary(props: &PlayerSummaryProps) -> Html {
+    let (active, queued) = props.players;
+
+    html!{
+        <>
+        {format!("{} are playing", format_count(active))}
+        { trend_arrow(props.trends.map(|trends| trends.0)) }
+        {format!(", with another {} in the queue", format_count(queued))}
+        { trend_arrow(props.trends.map(|trends| trends.1)) }
+        {". "}
+        <Sparkline points={props.history.clone()} />
+        if let Some((active, queued, servers)) = props.global {
+            <p class="is-size-7">
+                {format!("Global: {} playing, {} queued across {} servers", format_count(active), format_count(queued), format_count(servers))}
+            </p>
+        }
+        if let Some((updates, peak)) = props.peak {
+            <p class="is-size-7">{format!("Peak of the last {} updates: {} players", updates, format_count(peak))}</p>
+        }
+        <br/>
+        {format!("avg {:.0} players (median {}) across {} servers", props.average, props.median, format_count(props.servers))}
+        <br/>
+        {format!("{:.0}% of global capacity used", props.fill_rate)}
+        <ybc::Progress
+            classes={classes!("is-small", "mb-1", fill_color(props.fill_rate))}
+            max={100.0}
+            value={props.fill_rate.clamp(0.0, 100.0) as f32}
+        />
+        <br/>
+        {format!("Queue pressure: {:.0}%", props.queue_ratio)}
+        </>
+    }
+}
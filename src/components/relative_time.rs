@@ -0,0 +1,36 @@
+use gloo::timers::callback::Interval;
+use js_sys::Date;
+use yew::prelude::*;
+
+fn format_age(seconds: u64) -> String {
+    match seconds {
+        0..=59 => format!("{seconds}s ago"),
+        60..=3599 => format!("{}m ago", seconds / 60),
+        _ => format!("{}h ago", seconds / 3600),
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct RelativeTimeProps {
+    /// Milliseconds since the epoch, as from `Date::now`.
+    pub timestamp: f64,
+}
+
+/// How long ago `timestamp` was, e.g. "12s ago".
+/// Ticks on its own so the rest of the page doesn't re-render every second.
+#[function_component(RelativeTime)]
+pub fn relative_time(props: &RelativeTimeProps) -> Html {
+    let now = use_state(Date::now);
+
+    {
+        let now = now.clone();
+        use_effect_with_deps(move |_| {
+            let interval = Interval::new(1_000, move || now.set(Date::now()));
+            move || drop(interval)
+        }, ());
+    }
+
+    let seconds = ((*now - props.timestamp).max(0.0) / 1000.0) as u64;
+
+    html!{ {format_age(seconds)} }
+}
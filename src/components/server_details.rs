@@ -0,0 +1,74 @@
+use battlebit_api::ServerData;
+use gloo::events::EventListener;
+use wasm_bindgen::JsCast;
+use web_sys::KeyboardEvent;
+use yew::prelude::*;
+
+use crate::filter::anti_cheat_to_string;
+use crate::stats::{gamemode_to_string, map_to_string, region_to_string};
+
+#[derive(Properties, PartialEq)]
+pub struct ServerDetailsProps {
+    pub server: ServerData,
+    pub onclose: Callback<()>,
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value { "Yes" } else { "No" }
+}
+
+/// A modal listing every field of a server, closed by the backdrop, the close button or Escape.
+#[function_component(ServerDetails)]
+pub fn server_details(props: &ServerDetailsProps) -> Html {
+    {
+        let onclose = props.onclose.clone();
+        use_effect_with_deps(move |_| {
+            let listener = EventListener::new(&gloo::utils::document(), "keydown", move |event| {
+                let is_escape = event.dyn_ref::<KeyboardEvent>().is_some_and(|event| event.key() == "Escape");
+                if is_escape {
+                    onclose.emit(());
+                }
+            });
+
+            move || drop(listener)
+        }, ());
+    }
+
+    let server = &props.server;
+    let rows = [
+        ("Region", region_to_string(server.region())),
+        ("Map", format!("{} ({}, {})", map_to_string(server.map(), false), server.map_size(), server.day_night())),
+        ("Gamemode", gamemode_to_string(server.gamemode(), false)),
+        ("Players", format!("{}/{}", server.player_count(), server.max_players())),
+        ("Queue", server.queued_player_count().to_string()),
+        ("Tick rate", format!("{} Hz", server.hz())),
+        ("Anti-cheat", anti_cheat_to_string(server.anti_cheat())),
+        ("Official", String::from(yes_no(*server.is_official()))),
+        ("Password protected", String::from(yes_no(*server.has_password()))),
+        ("Build", server.build().clone()),
+    ];
+
+    let onclose = props.onclose.reform(|_: MouseEvent| ());
+
+    html!{
+        <div class="modal is-active">
+            <div class="modal-background" onclick={onclose.clone()}></div>
+            <div class="modal-content">
+                <ybc::Box>
+                    <ybc::Title size={ybc::HeaderSize::Is4}>{server.name()}</ybc::Title>
+                    <table class="table is-fullwidth is-narrow">
+                        <tbody>
+                        { for rows.into_iter().map(|(label, value)| html!{
+                            <tr>
+                                <th>{label}</th>
+                                <td>{value}</td>
+                            </tr>
+                        }) }
+                        </tbody>
+                    </table>
+                </ybc::Box>
+            </div>
+            <button class="modal-close is-large" aria-label="close" onclick={onclose}></button>
+        </div>
+    }
+}
@@ -0,0 +1,457 @@
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use battlebit_api::ServerData;
+use gloo::timers::callback::Timeout;
+use web_sys::{Element, HtmlInputElement};
+use yew::prelude::*;
+
+use crate::app::ServerContext;
+use crate::format::format_count;
+use crate::filter::anti_cheat_to_string;
+use crate::stats::{build_version, gamemode_to_string, map_to_string, region_to_string};
+use crate::storage;
+
+use super::fill_color;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Column {
+    Name,
+    Region,
+    Ping,
+    Map,
+    Gamemode,
+    Players,
+    Queue,
+    AntiCheat,
+    Official,
+    Version,
+}
+
+const COLUMNS: [(Column, &str); 10] = [
+    (Column::Name, "Name"),
+    (Column::Region, "Region"),
+    (Column::Ping, "Ping"),
+    (Column::Map, "Map"),
+    (Column::Gamemode, "Gamemode"),
+    (Column::Players, "Players"),
+    (Column::Queue, "Queue"),
+    (Column::AntiCheat, "Anti-cheat"),
+    (Column::Official, "Official"),
+    (Column::Version, "Version"),
+];
+
+/// Columns shown until the user picks their own.
+const DEFAULT_COLUMNS: [Column; 5] = [Column::Region, Column::Ping, Column::Map, Column::Gamemode, Column::Queue];
+
+impl Column {
+    fn key(&self) -> &'static str {
+        match self {
+            Column::Name => "name",
+            Column::Region => "region",
+            Column::Ping => "ping",
+            Column::Map => "map",
+            Column::Gamemode => "gamemode",
+            Column::Players => "players",
+            Column::Queue => "queue",
+            Column::AntiCheat => "anti-cheat",
+            Column::Official => "official",
+            Column::Version => "version",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Column> {
+        COLUMNS.into_iter().map(|(column, _)| column).find(|column| column.key() == key)
+    }
+
+    /// Name and players are always shown.
+    fn can_hide(&self) -> bool {
+        !matches!(self, Column::Name | Column::Players)
+    }
+
+    fn sort_key(&self, server: &ServerData, latencies: &HashMap<String, u32>) -> SortKey {
+        match self {
+            Column::Name => SortKey::Text(server.name().to_lowercase()),
+            Column::Region => SortKey::Text(region_to_string(server.region())),
+            // Servers without a measurement sort after every measured one.
+            Column::Ping => SortKey::Number(latency(server, latencies).unwrap_or(u32::MAX) as u64),
+            Column::Map => SortKey::Text(server.map().clone()),
+            Column::Gamemode => SortKey::Text(gamemode_to_string(server.gamemode(), false)),
+            Column::Players => SortKey::Number(*server.player_count() as u64),
+            Column::Queue => SortKey::Number(*server.queued_player_count() as u64),
+            Column::AntiCheat => SortKey::Text(anti_cheat_to_string(server.anti_cheat())),
+            Column::Official => SortKey::Number(*server.is_official() as u64),
+            Column::Version => SortKey::Version(build_version(server.build())),
+        }
+    }
+
+    fn cell(&self, server: &ServerData, latencies: &HashMap<String, u32>) -> Html {
+        match self {
+            Column::Name => html!{ <td>{server.name()}</td> },
+            Column::Region => html!{ <td>{region_to_string(server.region())}</td> },
+            Column::Ping => html!{
+                <td>
+                    if let Some(ms) = latency(server, latencies) {
+                        <ybc::Tag classes={classes!(ping_color(ms))}>{format!("{ms} ms")}</ybc::Tag>
+                    } else {
+                        {"—"}
+                    }
+                </td>
+            },
+            Column::Map => html!{ <td>{map_to_string(server.map(), false)}</td> },
+            Column::Gamemode => html!{ <td>{gamemode_to_string(server.gamemode(), false)}</td> },
+            Column::Players => {
+                let fill = fill_percentage(server);
+
+                html!{
+                    <td>
+                        {players_label(server)}
+                        <ybc::Progress
+                            classes={classes!("is-small", "mb-0", fill_color(fill))}
+                            max={100.0}
+                            value={fill as f32}
+                        />
+                    </td>
+                }
+            },
+            Column::Queue => html!{ <td>{format_count(*server.queued_player_count() as usize)}</td> },
+            Column::AntiCheat => html!{ <td>{anti_cheat_to_string(server.anti_cheat())}</td> },
+            Column::Official => html!{ <td>{if *server.is_official() { "Yes" } else { "No" }}</td> },
+            Column::Version => html!{ <td>{server.build()}</td> },
+        }
+    }
+}
+
+fn latency(server: &ServerData, latencies: &HashMap<String, u32>) -> Option<u32> {
+    latencies.get(&region_to_string(server.region())).copied()
+}
+
+/// Capped at 100%, servers briefly report more players than their maximum.
+fn fill_percentage(server: &ServerData) -> f64 {
+    match *server.max_players() {
+        0 => 0.0,
+        max => (*server.player_count() as f64 / max as f64 * 100.0).min(100.0),
+    }
+}
+
+fn players_label(server: &ServerData) -> String {
+    match *server.queued_player_count() {
+        0 => format!("{}/{}", server.player_count(), server.max_players()),
+        queued => format!("{}/{} (+{} queued)", server.player_count(), server.max_players(), format_count(queued as usize)),
+    }
+}
+
+fn ping_color(ms: u32) -> &'static str {
+    match ms {
+        ms if ms < 50 => "is-success",
+        ms if ms < 100 => "is-warning",
+        _ => "is-danger",
+    }
+}
+
+/// Delay after the last keystroke before the search is applied.
+const SEARCH_DEBOUNCE_MS: u32 = 250;
+
+/// Matches the row height of `.server-table-viewport` in index.scss, rows are windowed based on it.
+const ROW_HEIGHT_PX: usize = 48;
+
+/// Rows fitting in the scrollable viewport.
+const VIEWPORT_ROWS: usize = 12;
+
+/// Rows rendered above and below the viewport, so fast scrolling doesn't show blank space.
+const OVERSCAN_ROWS: usize = 6;
+
+/// Computed once per server before sorting, so comparisons don't allocate.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum SortKey {
+    Text(String),
+    Number(u64),
+    Version(Vec<u64>),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Sort {
+    column: Column,
+    ascending: bool,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ServerTableProps {
+    /// Called with the server whose row was clicked.
+    #[prop_or_default]
+    pub onselect: Callback<ServerData>,
+    /// Measured round trip times in milliseconds, keyed by region name. The API has no
+    /// per-client ping, so nothing provides these yet and the Ping column stays hidden
+    /// until some are passed in; the Region column is the proxy meanwhile.
+    #[prop_or_default]
+    pub latencies: Rc<HashMap<String, u32>>,
+    /// Names of starred servers, pinned to the top of the table.
+    #[prop_or_default]
+    pub favorites: HashSet<String>,
+    /// Starred servers missing from the server list, shown greyed out above the rest.
+    #[prop_or_default]
+    pub offline_favorites: Vec<String>,
+    #[prop_or_default]
+    pub ontogglefavorite: Callback<String>,
+    /// Names of servers that appeared with the last update, their rows fade in highlighted.
+    #[prop_or_default]
+    pub new_servers: HashSet<String>,
+    /// Names of servers that went away with the last update.
+    #[prop_or_default]
+    pub removed_servers: Vec<String>,
+}
+
+/// Compares the server list by identity. `ServerData` is only `PartialEq`, so comparing
+/// the lists themselves would walk every field of every server on each render.
+struct SameList(Rc<Vec<ServerData>>);
+
+impl PartialEq for SameList {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// Every server matching the active filters, with clickable headers to sort by a column.
+/// Only the rows around the scroll position are rendered, spacer rows stand in for the rest.
+#[function_component(ServerTable)]
+pub fn server_table(props: &ServerTableProps) -> Html {
+    let context = use_context::<ServerContext>().unwrap_or_default();
+    let sort = use_state(|| Sort { column: Column::Players, ascending: false });
+    let viewport = use_node_ref();
+    let first_row = use_state_eq(|| 0usize);
+    let search = use_state(String::new);
+    let search_debounce = use_mut_ref(|| None::<Timeout>);
+    let visible_columns = use_state(|| {
+        storage::load_table_columns()
+            .map(|keys| keys.iter().filter_map(|key| Column::from_key(key)).collect())
+            .unwrap_or_else(|| DEFAULT_COLUMNS.into_iter().collect::<HashSet<Column>>())
+    });
+
+    let scroll_to_top = {
+        let viewport = viewport.clone();
+        let first_row = first_row.clone();
+        Callback::from(move |_: ()| {
+            if let Some(element) = viewport.cast::<Element>() {
+                element.set_scroll_top(0);
+            }
+            first_row.set(0);
+        })
+    };
+
+    {
+        let scroll_to_top = scroll_to_top.clone();
+        use_effect_with_deps(move |_| scroll_to_top.emit(()), context.filters.clone());
+    }
+
+    // Indices into `context.servers` in display order. Memoized so scrolling doesn't re-sort.
+    let order = use_memo(|(SameList(servers), sort, search, favorites, latencies)| {
+        let search = search.trim().to_lowercase();
+        let mut keyed = servers.iter()
+            .enumerate()
+            .filter(|(_, server)| search.is_empty() || server.name().to_lowercase().contains(&search))
+            .map(|(index, server)| (favorites.contains(server.name()), sort.column.sort_key(server, latencies), index))
+            .collect::<Vec<(bool, SortKey, usize)>>();
+
+        keyed.sort_by(|a, b| {
+            let ordering = if sort.ascending { a.1.cmp(&b.1) } else { b.1.cmp(&a.1) };
+            b.0.cmp(&a.0).then(ordering)
+        });
+
+        keyed.into_iter().map(|(_, _, index)| index).collect::<Vec<usize>>()
+    }, (SameList(context.servers.clone()), *sort, (*search).clone(), props.favorites.clone(), props.latencies.clone()));
+
+    let total = order.len();
+    // A refresh or a search can shrink the list below the scroll position.
+    let first = (*first_row).min(total);
+    // Starting on an even row keeps the striping from flickering while scrolling.
+    let start = first.saturating_sub(OVERSCAN_ROWS) & !1;
+    let end = (first + VIEWPORT_ROWS + OVERSCAN_ROWS).min(total);
+
+    let onscroll = {
+        let first_row = first_row.clone();
+        let offline_rows = props.offline_favorites.len();
+        Callback::from(move |e: Event| {
+            let element: Element = e.target_unchecked_into();
+            let row = element.scroll_top().max(0) as usize / ROW_HEIGHT_PX;
+            first_row.set(row.saturating_sub(offline_rows));
+        })
+    };
+
+    let oninput = {
+        let search = search.clone();
+        let scroll_to_top = scroll_to_top.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let value = input.value();
+            let search = search.clone();
+            let scroll_to_top = scroll_to_top.clone();
+
+            // Replacing the timeout drops, and thereby cancels, the pending one.
+            *search_debounce.borrow_mut() = Some(Timeout::new(SEARCH_DEBOUNCE_MS, move || {
+                search.set(value);
+                scroll_to_top.emit(());
+            }));
+        })
+    };
+
+    // There's nothing to show in the Ping column until latencies are measured.
+    let is_available = |column: &Column| *column != Column::Ping || !props.latencies.is_empty();
+    let columns = COLUMNS.into_iter()
+        .filter(|(column, _)| is_available(column) && (!column.can_hide() || visible_columns.contains(column)))
+        .collect::<Vec<(Column, &str)>>();
+
+    let column_toggles = COLUMNS.into_iter()
+        .filter(|(column, _)| column.can_hide() && is_available(column))
+        .map(|(column, label)| {
+            let is_visible = visible_columns.contains(&column);
+            let onclick = {
+                let visible_columns = visible_columns.clone();
+                Callback::from(move |_: MouseEvent| {
+                    let mut columns = (*visible_columns).clone();
+                    if !columns.remove(&column) {
+                        columns.insert(column);
+                    }
+
+                    let keys = COLUMNS.into_iter()
+                        .filter(|(column, _)| columns.contains(column))
+                        .map(|(column, _)| column.key())
+                        .collect::<Vec<&str>>();
+                    storage::save_table_columns(&keys);
+                    visible_columns.set(columns);
+                })
+            };
+
+            html!{
+                <button class={classes!("button", is_visible.then_some("is-info"))} {onclick}>{label}</button>
+            }
+        })
+        .collect::<Html>();
+
+    let headers = columns.iter()
+        .copied()
+        .map(|(column, label)| {
+            let onclick = {
+                let sort = sort.clone();
+                let scroll_to_top = scroll_to_top.clone();
+                Callback::from(move |_: MouseEvent| {
+                    let ascending = if sort.column == column { !sort.ascending } else { true };
+                    sort.set(Sort { column, ascending });
+                    scroll_to_top.emit(());
+                })
+            };
+
+            let arrow = match (sort.column == column, sort.ascending) {
+                (true, true) => " ▲",
+                (true, false) => " ▼",
+                (false, _) => "",
+            };
+
+            html!{
+                <th class="is-clickable" {onclick}>{format!("{label}{arrow}")}</th>
+            }
+        })
+        .collect::<Html>();
+
+    // The star column plus every visible one.
+    let column_count = columns.len() + 1;
+
+    let star = |name: &str| {
+        let is_favorite = props.favorites.contains(name);
+        let onclick = {
+            let name = name.to_owned();
+            props.ontogglefavorite.reform(move |event: MouseEvent| {
+                // Starring shouldn't also open the detail modal.
+                event.stop_propagation();
+                name.clone()
+            })
+        };
+
+        html!{
+            <td class="is-clickable has-text-warning" title={if is_favorite { "Unstar" } else { "Star" }} {onclick}>
+                { if is_favorite { "★" } else { "☆" } }
+            </td>
+        }
+    };
+
+    let offline_rows = props.offline_favorites.iter()
+        .map(|name| html!{
+            <tr class="has-text-grey">
+                { star(name) }
+                <td colspan={(column_count - 1).to_string()}>
+                    {name}
+                    <ybc::Tag classes={classes!("ml-2")}>{"offline"}</ybc::Tag>
+                </td>
+            </tr>
+        })
+        .collect::<Html>();
+
+    let rows = order[start..end].iter()
+        .map(|&index| {
+            let server = &context.servers[index];
+            let onclick = {
+                let server = server.clone();
+                props.onselect.reform(move |_: MouseEvent| server.clone())
+            };
+
+            html!{
+                <tr class={classes!("is-clickable", props.new_servers.contains(server.name()).then_some("is-new-server"))} {onclick}>
+                    { star(server.name()) }
+                    { for columns.iter().map(|(column, _)| column.cell(server, &props.latencies)) }
+                </tr>
+            }
+        })
+        .collect::<Html>();
+
+    // One extra row for the sticky header.
+    let viewport_height = (VIEWPORT_ROWS + 1) * ROW_HEIGHT_PX;
+
+    let spacer = |rows: usize| html!{
+        <tr class="server-table-spacer" style={format!("height: {}px;", rows * ROW_HEIGHT_PX)}>
+            <td colspan={column_count.to_string()}></td>
+        </tr>
+    };
+
+    html!{
+        <ybc::Box classes={classes!("mt-4")}>
+            <ybc::Field>
+                <ybc::Control>
+                    <input class="input is-small" type="search" placeholder="Search servers in this table" {oninput} />
+                </ybc::Control>
+            </ybc::Field>
+            <div class="buttons are-small mb-2">
+                <span class="mr-2 is-size-7">{"Columns:"}</span>
+                { column_toggles }
+            </div>
+            if !props.removed_servers.is_empty() {
+                <p class="is-size-7 has-text-grey mb-2" title={props.removed_servers.join(", ")}>
+                    {format!(
+                        "{} server{} went away since the last refresh",
+                        format_count(props.removed_servers.len()),
+                        if props.removed_servers.len() == 1 { "" } else { "s" },
+                    )}
+                </p>
+            }
+            <div
+                ref={viewport}
+                class="table-container server-table-viewport"
+                style={format!("max-height: {viewport_height}px;")}
+                {onscroll}
+            >
+                <table class="table is-fullwidth is-striped is-hoverable is-narrow">
+                    <thead>
+                        <tr><th></th>{ headers }</tr>
+                    </thead>
+                    <tbody>
+                        { offline_rows }
+                        { spacer(start) }
+                        { rows }
+                        { spacer(total - end) }
+                    </tbody>
+                </table>
+            </div>
+            <p class="is-size-7 mt-2">
+                {format!("Showing {}–{} of {}", format_count((first + 1).min(total)), format_count((first + VIEWPORT_ROWS).min(total)), format_count(total))}
+            </p>
+        </ybc::Box>
+    }
+}
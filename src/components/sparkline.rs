@@ -0,0 +1,37 @@
+use yew::prelude::*;
+
+const WIDTH: f64 = 120.0;
+const HEIGHT: f64 = 24.0;
+
+#[derive(Properties, PartialEq)]
+pub struct SparklineProps {
+    pub points: Vec<usize>,
+}
+
+#[function_component(Sparkline)]
+pub fn sparkline(props: &SparklineProps) -> Html {
+    if props.points.len() < 2 {
+        return html!{};
+    }
+
+    let min = props.points.iter().copied().min().unwrap_or(0) as f64;
+    let max = props.points.iter().copied().max().unwrap_or(0) as f64;
+    let range = (max - min).max(1.0);
+    let step = WIDTH / (props.points.len() - 1) as f64;
+
+    let points = props.points.iter()
+        .enumerate()
+        .map(|(index, value)| {
+            let x = index as f64 * step;
+            let y = HEIGHT - (*value as f64 - min) / range * HEIGHT;
+            format!("{x:.1},{y:.1}")
+        })
+        .collect::<Vec<String>>()
+        .join(" ");
+
+    html!{
+        <svg class="is-block my-1" viewBox={format!("0 -1 {WIDTH} {}", HEIGHT + 2.0)} width={WIDTH.to_string()} height={HEIGHT.to_string()}>
+            <polyline points={points} fill="none" stroke="currentColor" stroke-width="1.5" />
+        </svg>
+    }
+}
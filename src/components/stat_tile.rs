@@ -0,0 +1,26 @@
+use yew::prelude::*;
+use ybc::TileCtx::{Child, Parent};
+
+use super::Theme;
+
+#[derive(Properties, PartialEq)]
+pub struct StatTileProps {
+    pub title: AttrValue,
+    #[prop_or_default]
+    pub children: Children,
+}
+
+/// A titled dashboard tile; the stat itself is passed as children.
+#[function_component(StatTile)]
+pub fn stat_tile(props: &StatTileProps) -> Html {
+    let theme = use_context::<Theme>().unwrap_or_default();
+
+    html!{
+        <ybc::Tile ctx={Parent}>
+            <ybc::Tile ctx={Child} classes={classes!("notification", theme.tile_class())}>
+                <ybc::Subtitle size={ybc::HeaderSize::Is3} classes={classes!(theme.text_class(), "mb-0")}>{props.title.clone()}</ybc::Subtitle>
+                { for props.children.iter() }
+            </ybc::Tile>
+        </ybc::Tile>
+    }
+}
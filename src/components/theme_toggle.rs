@@ -0,0 +1,69 @@
+use yew::prelude::*;
+
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub enum Theme {
+    #[default]
+    Dark,
+    Light,
+}
+
+impl Theme {
+    pub fn key(&self) -> &'static str {
+        match self {
+            Theme::Dark => "dark",
+            Theme::Light => "light",
+        }
+    }
+
+    pub fn from_key(value: &str) -> Option<Theme> {
+        [Theme::Dark, Theme::Light].into_iter().find(|theme| theme.key() == value)
+    }
+
+    pub fn toggled(&self) -> Theme {
+        match self {
+            Theme::Dark => Theme::Light,
+            Theme::Light => Theme::Dark,
+        }
+    }
+
+    pub fn hero_class(&self) -> &'static str {
+        match self {
+            Theme::Dark => "is-dark",
+            Theme::Light => "is-light",
+        }
+    }
+
+    pub fn tile_class(&self) -> &'static str {
+        match self {
+            Theme::Dark => "is-primary",
+            Theme::Light => "is-light",
+        }
+    }
+
+    pub fn text_class(&self) -> &'static str {
+        match self {
+            Theme::Dark => "has-text-white",
+            Theme::Light => "has-text-dark",
+        }
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ThemeToggleProps {
+    pub theme: Theme,
+    pub onchange: Callback<Theme>,
+}
+
+#[function_component(ThemeToggle)]
+pub fn theme_toggle(props: &ThemeToggleProps) -> Html {
+    let onclick = {
+        let theme = props.theme;
+        props.onchange.reform(move |_: MouseEvent| theme.toggled())
+    };
+
+    html!{
+        <ybc::Button classes={classes!("is-primary", "is-inverted", "is-outlined")} {onclick}>
+            { match props.theme { Theme::Dark => "Light mode", Theme::Light => "Dark mode" } }
+        </ybc::Button>
+    }
+}
@@ -0,0 +1,12 @@
+use yew::prelude::*;
+
+use crate::format::format_delta;
+
+/// A green/red arrow with the signed change, or nothing when unchanged or unknown.
+pub fn trend_arrow(delta: Option<i64>) -> Html {
+    match delta {
+        Some(delta) if delta > 0 => html!{ <span class="has-text-success ml-1">{format!("▲ {}", format_delta(delta))}</span> },
+        Some(delta) if delta < 0 => html!{ <span class="has-text-danger ml-1">{format!("▼ {}", format_delta(delta))}</span> },
+        _ => html!{},
+    }
+}
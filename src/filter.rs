@@ -0,0 +1,226 @@
+use battlebit_api::{ServerData, AntiCheat};
+
+use wasm_bindgen::JsValue;
+use web_sys::UrlSearchParams;
+
+use crate::stats::{gamemode_to_string, is_full, map_to_string, region_to_string};
+
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub enum ServerType {
+    #[default]
+    All,
+    Official,
+    Community,
+}
+
+impl ServerType {
+    pub const ALL: [ServerType; 3] = [ServerType::All, ServerType::Official, ServerType::Community];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ServerType::All => "All",
+            ServerType::Official => "Official",
+            ServerType::Community => "Community",
+        }
+    }
+
+    pub fn from_query(value: &str) -> Option<ServerType> {
+        ServerType::ALL.into_iter().find(|server_type| server_type.label().eq_ignore_ascii_case(value))
+    }
+}
+
+#[derive(Clone, PartialEq, Default)]
+pub struct FilterState {
+    pub search: String,
+    pub region: Option<String>,
+    pub gamemode: Option<String>,
+    pub map: Option<String>,
+    pub min_players: u32,
+    pub hide_empty: bool,
+    pub hide_full: bool,
+    pub anti_cheat: Option<AntiCheat>,
+    pub server_type: ServerType,
+    pub build: Option<String>,
+}
+
+impl FilterState {
+    pub fn from_query(query: &str) -> Self {
+        let mut filters = FilterState::default();
+
+        let Ok(params) = UrlSearchParams::new_with_str(query) else {
+            return filters;
+        };
+
+        let non_empty = |key: &str| params.get(key).filter(|value| !value.is_empty());
+
+        filters.search = non_empty("q").unwrap_or_default();
+        filters.region = non_empty("region");
+        filters.gamemode = non_empty("mode");
+        filters.map = non_empty("map");
+        filters.min_players = non_empty("min")
+            .and_then(|min| min.parse::<u32>().ok())
+            .map_or(0, |min| min.min(u8::MAX as u32));
+        filters.hide_empty = params.get("hide_empty").as_deref() == Some("1");
+        filters.hide_full = params.get("hide_full").as_deref() == Some("1");
+        filters.anti_cheat = non_empty("anticheat").and_then(|label| anti_cheat_from_string(&label));
+        filters.server_type = non_empty("type")
+            .and_then(|value| ServerType::from_query(&value))
+            .unwrap_or_default();
+        filters.build = non_empty("build");
+
+        filters
+    }
+
+    pub fn to_query(&self) -> String {
+        let Ok(params) = UrlSearchParams::new() else {
+            return String::new();
+        };
+
+        if !self.search.trim().is_empty() {
+            params.append("q", &self.search);
+        }
+        if let Some(region) = &self.region {
+            params.append("region", region);
+        }
+        if let Some(gamemode) = &self.gamemode {
+            params.append("mode", gamemode);
+        }
+        if let Some(map) = &self.map {
+            params.append("map", map);
+        }
+        if self.min_players > 0 {
+            params.append("min", &self.min_players.to_string());
+        }
+        if self.hide_empty {
+            params.append("hide_empty", "1");
+        }
+        if self.hide_full {
+            params.append("hide_full", "1");
+        }
+        if let Some(anti_cheat) = &self.anti_cheat {
+            params.append("anticheat", &anti_cheat_to_string(anti_cheat));
+        }
+        if self.server_type != ServerType::All {
+            params.append("type", &self.server_type.label().to_lowercase());
+        }
+        if let Some(build) = &self.build {
+            params.append("build", build);
+        }
+
+        params.to_string().into()
+    }
+
+    pub fn from_url() -> Self {
+        let query = gloo::utils::window().location().search().unwrap_or_default();
+        FilterState::from_query(&query)
+    }
+
+    pub fn write_to_url(&self) {
+        let window = gloo::utils::window();
+        let Ok(path) = window.location().pathname() else {
+            return;
+        };
+
+        let query = self.to_query();
+        let url = if query.is_empty() { path } else { format!("{path}?{query}") };
+
+        if let Ok(history) = window.history() {
+            let _ = history.replace_state_with_url(&JsValue::NULL, "", Some(&url));
+        }
+    }
+
+    pub fn active_count(&self) -> usize {
+        [
+            !self.search.trim().is_empty(),
+            self.region.is_some(),
+            self.gamemode.is_some(),
+            self.map.is_some(),
+            self.min_players > 0,
+            self.hide_empty,
+            self.hide_full,
+            self.anti_cheat.is_some(),
+            self.server_type != ServerType::All,
+            self.build.is_some(),
+        ]
+        .into_iter()
+        .filter(|active| *active)
+        .count()
+    }
+
+    pub fn matches(&self, server: &ServerData, merge_map_variants: bool) -> bool {
+        matches_query(server.name(), &self.search)
+            && self.region.as_ref().is_none_or(|region| region_to_string(server.region()) == *region)
+            && self.gamemode.as_ref().is_none_or(|gamemode| gamemode_to_string(server.gamemode(), false) == *gamemode)
+            && self.map.as_ref().is_none_or(|map| map_to_string(server.map(), merge_map_variants) == *map)
+            && *server.player_count() as u32 >= self.min_players
+            && (!self.hide_empty || *server.player_count() > 0)
+            && (!self.hide_full || !is_full(server))
+            && self.anti_cheat.is_none_or(|anti_cheat| *server.anti_cheat() == anti_cheat)
+            && match self.server_type {
+                ServerType::All => true,
+                ServerType::Official => *server.is_official(),
+                ServerType::Community => !*server.is_official(),
+            }
+            && self.build.as_ref().is_none_or(|build| server.build() == build)
+    }
+}
+
+/// Case-insensitive match of `query` against `name`, either as a substring
+/// or as a fuzzy subsequence ("infcon" matches "INFANTRY CONQUEST").
+fn matches_query(name: &str, query: &str) -> bool {
+    let query = query.trim().to_lowercase();
+    let name = name.to_lowercase();
+
+    if query.is_empty() || name.contains(&query) {
+        return true;
+    }
+
+    let mut name_chars = name.chars();
+
+    query.chars()
+        .filter(|c| !c.is_whitespace())
+        .all(|c| name_chars.any(|name_char| name_char == c))
+}
+
+pub const ANTI_CHEATS: [AntiCheat; 2] = [AntiCheat::EasyAntiCheat, AntiCheat::Unknown];
+
+pub fn anti_cheat_to_string(anti_cheat: &AntiCheat) -> String {
+    match anti_cheat {
+        AntiCheat::EasyAntiCheat => String::from("Easy Anti-Cheat"),
+        AntiCheat::Unknown => String::from("Other anti-cheat"),
+    }
+}
+
+pub fn anti_cheat_from_string(label: &str) -> Option<AntiCheat> {
+    ANTI_CHEATS.into_iter().find(|anti_cheat| anti_cheat_to_string(anti_cheat) == label)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::matches_query;
+
+    #[test]
+    fn matches_substrings_case_insensitively() {
+        assert!(matches_query("INFANTRY CONQUEST 24/7", "conquest"));
+        assert!(matches_query("INFANTRY CONQUEST 24/7", "  Conquest 24  "));
+        assert!(matches_query("INFANTRY CONQUEST 24/7", ""));
+    }
+
+    #[test]
+    fn matches_fuzzy_subsequences() {
+        assert!(matches_query("INFANTRY CONQUEST 24/7", "infcon"));
+        assert!(matches_query("INFANTRY CONQUEST 24/7", "inf con"));
+    }
+
+    #[test]
+    fn requires_query_characters_in_order() {
+        assert!(!matches_query("INFANTRY CONQUEST 24/7", "coninf"));
+        assert!(!matches_query("INFANTRY CONQUEST 24/7", "7infantry"));
+    }
+
+    #[test]
+    fn rejects_non_matches() {
+        assert!(!matches_query("INFANTRY CONQUEST 24/7", "rush"));
+        assert!(!matches_query("EU #1", "eu #12"));
+    }
+}
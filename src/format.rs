@@ -0,0 +1,49 @@
+/// Formats a count with the grouping separators of the browser's locale, e.g. `9,402`.
+#[cfg(target_arch = "wasm32")]
+pub fn format_count(n: usize) -> String {
+    js_sys::Number::from(n as f64)
+        .to_locale_string("default")
+        .into()
+}
+
+/// Off the browser there's no locale to ask, so commas are used.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn format_count(n: usize) -> String {
+    let digits = n.to_string();
+    let mut formatted = String::with_capacity(digits.len() + digits.len() / 3);
+
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            formatted.push(',');
+        }
+        formatted.push(digit);
+    }
+
+    formatted
+}
+
+/// Formats a change with an explicit sign, e.g. `+1,200` or `-35`.
+pub fn format_delta(delta: i64) -> String {
+    let sign = if delta < 0 { '-' } else { '+' };
+    format!("{sign}{}", format_count(delta.unsigned_abs() as usize))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_count, format_delta};
+
+    #[test]
+    fn groups_thousands() {
+        assert_eq!(format_count(0), "0");
+        assert_eq!(format_count(100), "100");
+        assert_eq!(format_count(1000), "1,000");
+        assert_eq!(format_count(1234567), "1,234,567");
+    }
+
+    #[test]
+    fn signs_deltas() {
+        assert_eq!(format_delta(1200), "+1,200");
+        assert_eq!(format_delta(-35), "-35");
+        assert_eq!(format_delta(0), "+0");
+    }
+}
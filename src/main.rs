@@ -1,6 +1,11 @@
 use app::App;
 
 mod app;
+mod components;
+mod filter;
+mod format;
+mod stats;
+mod storage;
 
 fn main() {
     yew::Renderer::<App>::new().render();
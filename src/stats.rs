@@ -0,0 +1,495 @@
+use std::collections::{BTreeMap, HashMap};
+
+use battlebit_api::{ServerData, Gamemode, Region};
+
+use itertools::Itertools;
+
+/// Numeric components of a build string, used to sort builds newest-first.
+pub fn build_version(build: &str) -> Vec<u64> {
+    build.split(|c: char| !c.is_ascii_digit())
+        .filter_map(|part| part.parse().ok())
+        .collect()
+}
+
+pub fn sum_players<'a>(servers: impl IntoIterator<Item = &'a ServerData>) -> (usize, usize) {
+    servers.into_iter().fold((0, 0), add_players)
+}
+
+/// Adds a server's active and queued players onto `counts`, saturating instead of overflowing.
+fn add_players(counts: (usize, usize), server: &ServerData) -> (usize, usize) {
+    (
+        counts.0.saturating_add(*server.player_count() as usize),
+        counts.1.saturating_add(*server.queued_player_count() as usize),
+    )
+}
+
+pub fn is_full(server: &ServerData) -> bool {
+    *server.max_players() > 0 && server.player_count() >= server.max_players()
+}
+
+const MAP_VARIANT_SUFFIXES: [&str; 2] = ["night", "dusk"];
+
+pub fn map_to_string(map: &str, merge_variants: bool) -> String {
+    if !merge_variants {
+        return map.to_string();
+    }
+
+    let lowercase = map.to_ascii_lowercase();
+
+    MAP_VARIANT_SUFFIXES.iter()
+        .find_map(|suffix| {
+            let base = lowercase.strip_suffix(suffix)?;
+            let base = base.strip_suffix(['_', ' ', '-'])?;
+            (!base.is_empty()).then(|| map[..base.len()].to_string())
+        })
+        .unwrap_or_else(|| map.to_string())
+}
+
+pub fn region_to_string(region: &Region) -> String {
+    match region {
+        Region::Europe => String::from("Europe"),
+        Region::Japan => String::from("Japan"),
+        Region::Australia => String::from("Australia"),
+        Region::America => String::from("America"),
+        Region::Brazil => String::from("Brazil"),
+        Region::Asia => String::from("Asia"),
+        Region::DeveloperServer => String::from("Developer Server"),
+        Region::Unknown => String::from("Unknown"),
+    }
+}
+
+pub fn gamemode_to_string(gamemode: &Gamemode, group_voxel: bool) -> String {
+    match gamemode {
+        Gamemode::VoxelFortify | Gamemode::VoxelTrench if group_voxel => String::from("Voxel"),
+        Gamemode::Conquest => String::from("Conquest"),
+        Gamemode::InfanteryConquest => String::from("Infantery Conquest"),
+        Gamemode::Rush => String::from("Rush"),
+        Gamemode::Frontline => String::from("Frontline"),
+        Gamemode::Domination => String::from("Domination"),
+        Gamemode::Elimination => String::from("Elimination"),
+        Gamemode::TeamDeathmatch => String::from("Team Deathmatch"),
+        Gamemode::CaptureTheFlag => String::from("Capture The Flag"),
+        Gamemode::VoxelFortify => String::from("Voxel Fortify"),
+        Gamemode::VoxelTrench => String::from("Voxel Trench"),
+        Gamemode::FreeForAll => String::from("Free For All"),
+        Gamemode::Gamemode19 => String::from("Gamemode 19"),
+        Gamemode::Unknown => String::from("Unknown"),
+    }
+}
+
+/// Aggregations over an already filtered set of servers.
+pub struct Stats<'a> {
+    servers: Vec<&'a ServerData>,
+    merge_map_variants: bool,
+    group_voxel: bool,
+}
+
+impl<'a> Stats<'a> {
+    pub fn new(servers: Vec<&'a ServerData>, merge_map_variants: bool, group_voxel: bool) -> Self {
+        Stats { servers, merge_map_variants, group_voxel }
+    }
+
+    pub fn region_count(&self) -> HashMap<String, usize> {
+        let counts = self.servers.iter().copied().fold(HashMap::new(), |mut counts, server| {
+            let region = region_to_string(server.region());
+
+            match counts.get_mut(&region) {
+                Some(val) => *val += 1,
+                None => { counts.insert(region, 1usize); },
+            }
+
+            counts
+        });
+
+        counts
+    }
+
+    pub fn region_share(&self) -> Vec<(String, usize, f64)> {
+        let total = self.server_count();
+
+        if total == 0 {
+            return Vec::new();
+        }
+
+        self.region_count()
+            .into_iter()
+            .map(|(region, count)| {
+                let share = (count as f64 / total as f64 * 1000.0).round() / 10.0;
+                (region, count, share)
+            })
+            .sorted_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)))
+            .collect()
+    }
+
+    pub fn per_region_player_count(&self) -> HashMap<String, (usize, usize)> {
+        self.servers.iter().copied().fold(HashMap::new(), |mut counts, server| {
+            let region = region_to_string(server.region());
+            let entry = counts.entry(region).or_insert((0, 0));
+
+            entry.0 = entry.0.saturating_add(*server.player_count() as usize);
+            entry.1 = entry.1.saturating_add(*server.queued_player_count() as usize);
+
+            counts
+        })
+    }
+
+    pub fn average_players_per_region(&self) -> HashMap<String, f64> {
+        let region_players = self.per_region_player_count();
+
+        self.region_count()
+            .into_iter()
+            .filter(|(_, count)| *count > 0)
+            .map(|(region, count)| {
+                let players = region_players.get(&region).map_or(0, |players| players.0);
+                (region, players as f64 / count as f64)
+            })
+            .collect()
+    }
+
+    pub fn regions_by_players(&self) -> Vec<(String, usize)> {
+        self.per_region_player_count()
+            .into_iter()
+            .map(|(region, players)| (region, players.0))
+            .sorted_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)))
+            .collect()
+    }
+
+    pub fn busiest_region(&self) -> Option<(String, usize)> {
+        self.regions_by_players().into_iter().next()
+    }
+
+    pub fn count_maps(&self, merge_variants: bool) -> HashMap<String, usize> {
+        self.servers.iter().copied().fold(HashMap::new(), |mut counts, server| {
+            let map = map_to_string(server.map(), merge_variants);
+
+            match counts.get_mut(&map) {
+                Some(val) => *val += 1,
+                None => { counts.insert(map, 1usize); },
+            }
+            counts
+        })
+    }
+
+    pub fn map_count(&self) -> HashMap<String, usize> {
+        self.count_maps(false)
+    }
+
+    pub fn map_count_normalized(&self) -> HashMap<String, usize> {
+        self.count_maps(true)
+    }
+
+    pub fn per_map_player_count(&self) -> HashMap<String, usize> {
+        self.servers.iter().copied().fold(HashMap::new(), |mut counts, server| {
+            let map = map_to_string(server.map(), self.merge_map_variants);
+
+            let players = counts.entry(map).or_insert(0);
+            *players = players.saturating_add(*server.player_count() as usize);
+            counts
+        })
+    }
+
+    pub fn busiest_map(&self) -> Option<(String, usize)> {
+        self.per_map_player_count()
+            .into_iter()
+            .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0)))
+    }
+
+    pub fn maps_by_players(&self) -> Vec<(String, usize, usize)> {
+        let map_players = self.per_map_player_count();
+        let map_counts = if self.merge_map_variants {
+            self.map_count_normalized()
+        } else {
+            self.map_count()
+        };
+
+        map_counts
+            .into_iter()
+            .map(|(item, count)| {
+                let players = map_players.get(&item).copied().unwrap_or(0);
+                (item, count, players)
+            })
+            .sorted_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.cmp(&b.0)))
+            .collect()
+    }
+
+    pub fn player_count(&self) -> (usize, usize) {
+        sum_players(self.servers.iter().copied())
+    }
+
+    pub fn queue_ratio(&self) -> f64 {
+        let (active, queued) = self.player_count();
+
+        if active == 0 {
+            return 0.0;
+        }
+
+        queued as f64 / active as f64 * 100.0
+    }
+
+    pub fn server_count(&self) -> usize {
+        self.servers.len()
+    }
+
+    pub fn total_capacity(&self) -> usize {
+        self.servers.iter().copied().fold(0, |capacity, server| {
+            capacity.saturating_add(*server.max_players() as usize)
+        })
+    }
+
+    pub fn fill_rate(&self) -> f64 {
+        let capacity = self.total_capacity();
+
+        if capacity == 0 {
+            return 0.0;
+        }
+
+        self.player_count().0 as f64 / capacity as f64 * 100.0
+    }
+
+    pub fn empty_server_count(&self) -> usize {
+        self.servers.iter().copied()
+            .filter(|server| *server.player_count() == 0)
+            .count()
+    }
+
+    pub fn full_server_count(&self) -> usize {
+        self.servers.iter().copied()
+            .filter(|server| is_full(server))
+            .count()
+    }
+
+    pub fn servers_above(&self, threshold: u32) -> usize {
+        self.servers.iter().copied()
+            .filter(|server| *server.player_count() as u32 >= threshold)
+            .count()
+    }
+
+    pub fn busiest_server(&self) -> Option<&'a ServerData> {
+        self.servers.iter().copied().max_by_key(|server| *server.player_count())
+    }
+
+    pub fn most_queued_server(&self) -> Option<&'a ServerData> {
+        self.servers.iter().copied().max_by(|a, b| {
+            a.queued_player_count().cmp(b.queued_player_count())
+                .then_with(|| a.player_count().cmp(b.player_count()))
+        })
+    }
+
+    pub fn average_players_per_server(&self) -> f64 {
+        let server_count = self.server_count();
+
+        if server_count == 0 {
+            return 0.0;
+        }
+
+        self.player_count().0 as f64 / server_count as f64
+    }
+
+    pub fn median_player_count(&self) -> u32 {
+        let counts = self.servers.iter().copied()
+            .map(|server| *server.player_count() as u32)
+            .sorted()
+            .collect::<Vec<u32>>();
+
+        if counts.is_empty() {
+            return 0;
+        }
+
+        let middle = counts.len() / 2;
+
+        if counts.len() % 2 == 0 {
+            (counts[middle - 1] + counts[middle]) / 2
+        } else {
+            counts[middle]
+        }
+    }
+
+    pub fn player_count_std_dev(&self) -> f64 {
+        if self.server_count() < 2 {
+            return 0.0;
+        }
+
+        let mean = self.average_players_per_server();
+        let variance = self.servers.iter().copied()
+            .map(|server| (*server.player_count() as f64 - mean).powi(2))
+            .sum::<f64>() / self.server_count() as f64;
+
+        variance.sqrt()
+    }
+
+    pub fn count_gamemodes(&self, group_voxel: bool) -> HashMap<String, usize> {
+        self.servers.iter().copied().fold(HashMap::new(), |mut counts, server| {
+            let gamemode = gamemode_to_string(server.gamemode(), group_voxel);
+
+            match counts.get_mut(&gamemode) {
+                Some(val) => *val += 1,
+                None => { counts.insert(gamemode, 1usize); },
+            }
+
+            counts
+        })
+    }
+
+    pub fn population_histogram(&self, bucket_size: u32) -> BTreeMap<u32, usize> {
+        if bucket_size == 0 {
+            return BTreeMap::new();
+        }
+
+        let max_capacity = self.servers.iter().copied()
+            .map(|server| *server.max_players() as u32)
+            .max()
+            .unwrap_or(0);
+        let top_bucket = max_capacity.saturating_sub(1) / bucket_size * bucket_size;
+
+        self.servers.iter().copied().fold(BTreeMap::new(), |mut buckets, server| {
+            let bucket = (*server.player_count() as u32 / bucket_size * bucket_size).min(top_bucket);
+
+            *buckets.entry(bucket).or_insert(0) += 1;
+            buckets
+        })
+    }
+
+    pub fn gamemode_count(&self) -> HashMap<String, usize> {
+        self.count_gamemodes(false)
+    }
+
+    pub fn gamemode_count_grouped(&self) -> HashMap<String, usize> {
+        self.count_gamemodes(true)
+    }
+
+    pub fn per_gamemode_player_count(&self) -> HashMap<String, usize> {
+        self.servers.iter().copied().fold(HashMap::new(), |mut counts, server| {
+            let gamemode = gamemode_to_string(server.gamemode(), self.group_voxel);
+
+            let players = counts.entry(gamemode).or_insert(0);
+            *players = players.saturating_add(*server.player_count() as usize);
+            counts
+        })
+    }
+
+    pub fn gamemodes_by_players(&self) -> Vec<(String, usize, usize)> {
+        let gamemode_players = self.per_gamemode_player_count();
+        let gamemode_counts = if self.group_voxel {
+            self.gamemode_count_grouped()
+        } else {
+            self.gamemode_count()
+        };
+
+        gamemode_counts
+            .into_iter()
+            .map(|(item, count)| {
+                let players = gamemode_players.get(&item).copied().unwrap_or(0);
+                (item, count, players)
+            })
+            .sorted_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.cmp(&b.0)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(name: &str, map: &str, gamemode: &str, players: u8) -> ServerData {
+        serde_json::from_value(server_json(name, map, gamemode, players)).unwrap()
+    }
+
+    fn server_in(region: &str, name: &str) -> ServerData {
+        let mut json = server_json(name, "Azagor", "CONQ", 10);
+        json["Region"] = region.into();
+
+        serde_json::from_value(json).unwrap()
+    }
+
+    fn server_json(name: &str, map: &str, gamemode: &str, players: u8) -> serde_json::Value {
+        serde_json::json!({
+            "Name": name,
+            "Map": map,
+            "MapSize": "Big",
+            "Gamemode": gamemode,
+            "Region": "Europe_Central",
+            "Players": players,
+            "QueuePlayers": 0,
+            "MaxPlayers": 254,
+            "Hz": 60,
+            "DayNight": "Day",
+            "IsOfficial": true,
+            "HasPassword": false,
+            "AntiCheat": "EAC",
+            "Build": "Production 2.1.3",
+        })
+    }
+
+    fn with_players(players: &[u8]) -> Vec<ServerData> {
+        players.iter()
+            .enumerate()
+            .map(|(i, players)| server(&format!("Server {i}"), "Azagor", "CONQ", *players))
+            .collect()
+    }
+
+    #[test]
+    fn median_of_no_servers_is_zero() {
+        assert_eq!(Stats::new(Vec::new(), false, false).median_player_count(), 0);
+    }
+
+    #[test]
+    fn median_of_odd_count_is_middle_value() {
+        let servers = with_players(&[90, 10, 40]);
+        assert_eq!(Stats::new(servers.iter().collect(), false, false).median_player_count(), 40);
+    }
+
+    #[test]
+    fn median_of_even_count_averages_middle_values() {
+        let servers = with_players(&[100, 10, 40, 20]);
+        assert_eq!(Stats::new(servers.iter().collect(), false, false).median_player_count(), 30);
+    }
+
+    #[test]
+    fn player_totals_saturate() {
+        let server = server("A", "Azagor", "CONQ", 50);
+
+        assert_eq!(add_players((usize::MAX - 1, usize::MAX), &server), (usize::MAX, usize::MAX));
+        assert_eq!(sum_players([&server, &server]), (100, 0));
+    }
+
+    #[test]
+    fn ties_in_players_are_ordered_by_name() {
+        let servers = [
+            server("A", "Dustydew", "RUSH", 50),
+            server("B", "Azagor", "CONQ", 50),
+            server("C", "Basra", "DOMI", 50),
+            server("D", "Wakistan", "TDM", 80),
+        ];
+
+        // Every run hashes the per-map counts with a fresh seed, so an unstable order would show up here.
+        for _ in 0..10 {
+            let stats = Stats::new(servers.iter().collect(), false, false);
+
+            let maps = stats.maps_by_players().into_iter().map(|(map, _, _)| map).collect::<Vec<String>>();
+            assert_eq!(maps, ["Wakistan", "Azagor", "Basra", "Dustydew"]);
+
+            let gamemodes = stats.gamemodes_by_players().into_iter().map(|(gamemode, _, _)| gamemode).collect::<Vec<String>>();
+            assert_eq!(gamemodes, ["Team Deathmatch", "Conquest", "Domination", "Rush"]);
+        }
+    }
+
+    #[test]
+    fn servers_of_one_region_share_an_entry() {
+        let servers = [
+            server_in("Europe_Central", "A"),
+            server_in("Europe_Central", "B"),
+            server_in("America_Central", "C"),
+        ];
+
+        let counts = Stats::new(servers.iter().collect(), false, false).region_count();
+        assert_eq!(counts, HashMap::from([(String::from("Europe"), 2), (String::from("America"), 1)]));
+    }
+
+    #[test]
+    fn miscased_regions_count_as_unknown() {
+        let servers = [server_in("europe_central", "A"), server_in("EUROPE_CENTRAL", "B")];
+
+        let counts = Stats::new(servers.iter().collect(), false, false).region_count();
+        assert_eq!(counts, HashMap::from([(String::from("Unknown"), 2)]));
+    }
+}
@@ -0,0 +1,106 @@
+use std::collections::{HashSet, VecDeque};
+
+use gloo::storage::{LocalStorage, Storage};
+use battlebit_api::ServerData;
+use serde::{Deserialize, Serialize};
+
+const HISTORY_KEY: &str = "battlebit-stats.history";
+const SERVERS_KEY: &str = "battlebit-stats.servers";
+const REFRESH_SECS_KEY: &str = "battlebit-stats.refresh-secs";
+const THEME_KEY: &str = "battlebit-stats.theme";
+const FAVORITES_KEY: &str = "battlebit-stats.favorites";
+const TABLE_COLUMNS_KEY: &str = "battlebit-stats.table-columns";
+const HISTORY_VERSION: u32 = 1;
+
+/// History entries older than this are dropped when loading.
+const HISTORY_MAX_AGE_MS: f64 = 24.0 * 60.0 * 60.0 * 1000.0;
+
+/// Cached server lists older than this are ignored when loading.
+const SERVERS_MAX_AGE_MS: f64 = 10.0 * 60.0 * 1000.0;
+
+#[derive(Serialize, Deserialize)]
+struct StoredHistory {
+    version: u32,
+    points: Vec<(f64, usize)>,
+}
+
+pub fn load_history(now: f64) -> VecDeque<(f64, usize)> {
+    match LocalStorage::get::<StoredHistory>(HISTORY_KEY) {
+        Ok(stored) if stored.version == HISTORY_VERSION => stored.points
+            .into_iter()
+            .filter(|(timestamp, _)| now - timestamp <= HISTORY_MAX_AGE_MS)
+            .collect(),
+        Ok(_) | Err(_) => {
+            LocalStorage::delete(HISTORY_KEY);
+            VecDeque::new()
+        },
+    }
+}
+
+pub fn save_history(history: &VecDeque<(f64, usize)>) {
+    let stored = StoredHistory {
+        version: HISTORY_VERSION,
+        points: history.iter().copied().collect(),
+    };
+
+    let _ = LocalStorage::set(HISTORY_KEY, stored);
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredServers {
+    saved_at: f64,
+    servers: Vec<ServerData>,
+}
+
+/// Returns the cached server list along with the time it was fetched at.
+pub fn load_servers(now: f64) -> Option<(f64, Vec<ServerData>)> {
+    match LocalStorage::get::<StoredServers>(SERVERS_KEY) {
+        Ok(stored) if now - stored.saved_at <= SERVERS_MAX_AGE_MS => Some((stored.saved_at, stored.servers)),
+        Ok(_) | Err(_) => {
+            LocalStorage::delete(SERVERS_KEY);
+            None
+        },
+    }
+}
+
+pub fn save_servers(saved_at: f64, servers: &[ServerData]) {
+    let stored = StoredServers {
+        saved_at,
+        servers: servers.to_vec(),
+    };
+
+    let _ = LocalStorage::set(SERVERS_KEY, stored);
+}
+
+pub fn load_refresh_secs() -> Option<u32> {
+    LocalStorage::get(REFRESH_SECS_KEY).ok()
+}
+
+pub fn save_refresh_secs(refresh_secs: u32) {
+    let _ = LocalStorage::set(REFRESH_SECS_KEY, refresh_secs);
+}
+
+pub fn load_theme() -> Option<String> {
+    LocalStorage::get(THEME_KEY).ok()
+}
+
+pub fn save_theme(theme: &str) {
+    let _ = LocalStorage::set(THEME_KEY, theme);
+}
+
+/// Favorite servers are stored by name, since the API has no stable server id.
+pub fn load_favorites() -> HashSet<String> {
+    LocalStorage::get(FAVORITES_KEY).unwrap_or_default()
+}
+
+pub fn save_favorites(favorites: &HashSet<String>) {
+    let _ = LocalStorage::set(FAVORITES_KEY, favorites);
+}
+
+pub fn load_table_columns() -> Option<Vec<String>> {
+    LocalStorage::get(TABLE_COLUMNS_KEY).ok()
+}
+
+pub fn save_table_columns(columns: &[&str]) {
+    let _ = LocalStorage::set(TABLE_COLUMNS_KEY, columns);
+}